@@ -1,8 +1,48 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint, MintTo};
+use anchor_lang::Discriminator;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, TransferChecked, Mint, MintTo, Burn};
+use anchor_spl::token::{self, Token};
+use anchor_spl::token::spl_token::native_mint;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use fee_rewards::program::FeeRewards;
+use project_status_tracker::program::ProjectStatusTracker;
+use project_status_tracker::{ProjectTracker, TokenStatus};
+use category_tracker::program::CategoryTracker;
+use protocol_config::ProtocolConfig;
+
+pub mod math;
 
 declare_id!("SwApCoNtRaCt1111111111111111111111111111111");
 
+/// Custody program whose LP vaults may disable their token's pool
+pub mod lp_custody_program {
+    use super::*;
+    declare_id!("423GhdzXo7gogEHQz5Np2FfmF28P3B3acHufnU8WcHey");
+}
+
+// Buys that still earn Early Buyer points in category_tracker
+pub const EARLY_BUYER_LIMIT: u64 = 10;
+
+// Number of price observations kept per pool for TWAP queries
+pub const TWAP_OBSERVATIONS: usize = 16;
+// Minimum seconds between stored observations (16 x 5 min = 80 min of history)
+pub const TWAP_OBSERVATION_INTERVAL: i64 = 300;
+
+// Max drift between booked reserves and actual pool balances before swaps are refused (1%)
+pub const RESERVE_TOLERANCE_BPS: u64 = 100;
+
+// Most legs a batch_swap may carry; each leg makes several CPIs, so a full batch
+// needs a raised compute unit limit
+pub const MAX_BATCH_LEGS: usize = 5;
+
+// Remaining accounts per batch_swap leg, in order: swap pool, token mint, pool token
+// account, pool SOL account, user token account, token program, project tracker,
+// token volume tracker, buyer guard and early buy tracker (the last two are this
+// program's ID when there is none)
+pub const BATCH_LEG_ACCOUNTS: usize = 10;
+
 #[program]
 pub mod apeout_swap {
     use super::*;
@@ -19,26 +59,41 @@ pub mod apeout_swap {
         let token_mint = ctx.accounts.token_mint.key();
         let lp_mint = ctx.accounts.lp_mint.key();
         let bump = *ctx.bumps.get("swap_pool").unwrap();
+        let launch_protection = LaunchProtection::from_config(&ctx.accounts.protocol_config);
+        let fee_schedule = FeeSchedule::from_config(&ctx.accounts.protocol_config);
         
         // Transfer tokens from creator to pool
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.creator_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
                     to: ctx.accounts.pool_token_account.to_account_info(),
                     authority: ctx.accounts.creator.to_account_info(),
                 },
             ),
             token_amount,
+            ctx.accounts.token_mint.decimals,
         )?;
 
+        // Reserves track what the pool holds after any Token-2022 transfer fee
+        let token_amount = math::sub(token_amount, transfer_fee(&ctx.accounts.token_mint.to_account_info(), token_amount)?)?;
+
         // Transfer SOL from creator to pool
-        **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? -= sol_amount;
-        **ctx.accounts.pool_sol_account.to_account_info().try_borrow_mut_lamports()? += sol_amount;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.pool_sol_account.to_account_info(),
+                },
+            ),
+            sol_amount,
+        )?;
 
-        // Calculate initial LP tokens using geometric mean (sqrt(x * y))
-        let initial_lp = (token_amount as f64 * sol_amount as f64).sqrt() as u64;
+        // Initial LP supply is the geometric mean sqrt(x * y); MINIMUM_LIQUIDITY of it is never minted
+        let (initial_lp, lp_to_mint) = math::initial_lp_supply(token_amount, sol_amount)?;
         
         // Create seeds for signing
         let seeds = &[
@@ -48,7 +103,118 @@ pub mod apeout_swap {
         ];
         
         // Mint LP tokens to custody contract (100% locked)
-        token::mint_to(
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.custody_lp_account.to_account_info(),
+                    authority: ctx.accounts.swap_pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            lp_to_mint,
+        )?;
+
+        // Initialize pool data AFTER token operations
+        let swap_pool = &mut ctx.accounts.swap_pool;
+        swap_pool.initialize(token_mint, lp_mint, bump, clock.unix_timestamp, QuoteMode::NativeSol, launch_protection);
+        swap_pool.fee_schedule = fee_schedule;
+        swap_pool.token_reserve = token_amount;
+        swap_pool.sol_reserve = sol_amount;
+        swap_pool.total_lp_supply = initial_lp;
+
+        msg!("Swap pool initialized: {} tokens, {} SOL, {} LP tokens minted ({} locked)",
+             token_amount, sol_amount, lp_to_mint, math::MINIMUM_LIQUIDITY);
+
+        Ok(())
+    }
+
+    /// Initialize a pool whose SOL side is a WSOL token vault owned by the pool PDA
+    pub fn init_wsol_pool(
+        ctx: Context<InitWsolPool>,
+        token_amount: u64,
+        sol_amount: u64
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        // Extract values before borrowing issues
+        let token_mint = ctx.accounts.token_mint.key();
+        let lp_mint = ctx.accounts.lp_mint.key();
+        let bump = *ctx.bumps.get("swap_pool").unwrap();
+        let launch_protection = LaunchProtection::from_config(&ctx.accounts.protocol_config);
+        let fee_schedule = FeeSchedule::from_config(&ctx.accounts.protocol_config);
+        let wsol_bump = *ctx.bumps.get("pool_wsol_vault").unwrap();
+
+        // Create the WSOL vault. It always lives under SPL Token (native mint),
+        // whichever token program the launched token uses.
+        let wsol_seeds = &[
+            b"pool_wsol",
+            token_mint.as_ref(),
+            &[wsol_bump],
+        ];
+        let rent = Rent::get()?.minimum_balance(token::TokenAccount::LEN);
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.pool_wsol_vault.to_account_info(),
+                },
+                &[wsol_seeds],
+            ),
+            rent,
+            token::TokenAccount::LEN as u64,
+            &token::ID,
+        )?;
+        token::initialize_account3(CpiContext::new(
+            ctx.accounts.wsol_token_program.to_account_info(),
+            token::InitializeAccount3 {
+                account: ctx.accounts.pool_wsol_vault.to_account_info(),
+                mint: ctx.accounts.native_mint.to_account_info(),
+                authority: ctx.accounts.swap_pool.to_account_info(),
+            },
+        ))?;
+
+        // Transfer tokens from creator to pool
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.creator_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        // Reserves track what the pool holds after any Token-2022 transfer fee
+        let token_amount = math::sub(token_amount, transfer_fee(&ctx.accounts.token_mint.to_account_info(), token_amount)?)?;
+
+        // Wrap the creator's SOL into the vault
+        wrap_sol(
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.pool_wsol_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.wsol_token_program.to_account_info(),
+            sol_amount,
+        )?;
+
+        // Initial LP supply is the geometric mean sqrt(x * y); MINIMUM_LIQUIDITY of it is never minted
+        let (initial_lp, lp_to_mint) = math::initial_lp_supply(token_amount, sol_amount)?;
+
+        // Create seeds for signing
+        let seeds = &[
+            b"swap_pool",
+            token_mint.as_ref(),
+            &[bump],
+        ];
+
+        // Mint LP tokens to custody contract (100% locked)
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
@@ -58,24 +224,103 @@ pub mod apeout_swap {
                 },
                 &[seeds],
             ),
-            initial_lp,
+            lp_to_mint,
         )?;
 
         // Initialize pool data AFTER token operations
         let swap_pool = &mut ctx.accounts.swap_pool;
-        swap_pool.token_mint = token_mint;
+        swap_pool.initialize(token_mint, lp_mint, bump, clock.unix_timestamp, QuoteMode::WrappedSol, launch_protection);
+        swap_pool.fee_schedule = fee_schedule;
         swap_pool.token_reserve = token_amount;
         swap_pool.sol_reserve = sol_amount;
-        swap_pool.lp_mint = lp_mint;
         swap_pool.total_lp_supply = initial_lp;
-        swap_pool.fee_rate = 30; // 0.3% fee (30 basis points)
-        swap_pool.is_active = true;
-        swap_pool.created_at = clock.unix_timestamp;
-        swap_pool.bump = bump;
 
-        msg!("Swap pool initialized: {} tokens, {} SOL, {} LP tokens minted",
-             token_amount, sol_amount, initial_lp);
+        msg!("WSOL swap pool initialized: {} tokens, {} SOL, {} LP tokens minted ({} locked)",
+             token_amount, sol_amount, lp_to_mint, math::MINIMUM_LIQUIDITY);
+
+        Ok(())
+    }
+
+    /// Initialize a wallet's buy tracking for a pool's anti-sniper launch window
+    pub fn initialize_buyer_guard(ctx: Context<InitializeBuyerGuard>) -> Result<()> {
+        let guard = &mut ctx.accounts.buyer_guard;
+        guard.swap_pool = ctx.accounts.swap_pool.key();
+        guard.buyer = ctx.accounts.buyer.key();
+        guard.last_slot = 0;
+        guard.bought_in_slot = 0;
+        guard.total_bought = 0;
+        guard.bump = *ctx.bumps.get("buyer_guard").unwrap();
+
+        msg!("Buyer guard initialized for {} on pool {}", guard.buyer, guard.swap_pool);
+        Ok(())
+    }
+
+    /// Create the caller's LP position on a pool, which tracks the fees earned by
+    /// the LP tokens in their LP token account
+    pub fn initialize_lp_position(ctx: Context<InitializeLpPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.lp_position;
+        position.owner = ctx.accounts.owner.key();
+        position.swap_pool = ctx.accounts.swap_pool.key();
+        position.fee_growth_checkpoint = ctx.accounts.swap_pool.fee_growth_per_share;
+        position.fees_accrued = 0;
+        position.sol_value_added = 0;
+        position.bump = *ctx.bumps.get("lp_position").unwrap();
+
+        msg!("LP position initialized for {} on pool {}", position.owner, position.swap_pool);
+        Ok(())
+    }
+
+    /// Create the caller's referrer account, which collects their share of the
+    /// fees on swaps that name it
+    pub fn initialize_referrer(ctx: Context<InitializeReferrer>) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.owner = ctx.accounts.owner.key();
+        referrer.volume_referred = 0;
+        referrer.fees_earned = 0;
+        referrer.fees_unclaimed = 0;
+        referrer.unique_traders = 0;
+        referrer.bump = *ctx.bumps.get("referrer").unwrap();
+
+        msg!("Referrer initialized for {}", referrer.owner);
+        Ok(())
+    }
+
+    /// Record that the caller was referred by `referrer`. Each trader can be
+    /// registered once; this is what counts unique referred traders.
+    pub fn register_referral(ctx: Context<RegisterReferral>) -> Result<()> {
+        let referral = &mut ctx.accounts.referral;
+        referral.trader = ctx.accounts.trader.key();
+        referral.referrer = ctx.accounts.referrer.key();
+        referral.registered_at = Clock::get()?.unix_timestamp;
+        referral.bump = *ctx.bumps.get("referral").unwrap();
+
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.unique_traders = referrer.unique_traders.checked_add(1).ok_or(SwapError::MathOverflow)?;
+
+        msg!("Trader {} referred by {}", referral.trader, referrer.owner);
+        Ok(())
+    }
+
+    /// Withdraw the referral fees accrued so far
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        let amount = ctx.accounts.referrer.fees_unclaimed;
+        require!(amount > 0, SwapError::InvalidAmount);
 
+        // The referrer PDA is owned by this program, so its lamports move directly
+        **ctx.accounts.referrer.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.fees_unclaimed = 0;
+
+        emit!(ReferralFeesClaimed {
+            referrer: referrer.key(),
+            owner: referrer.owner,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Referrer {} claimed {} lamports", referrer.owner, amount);
         Ok(())
     }
 
@@ -84,9 +329,217 @@ pub mod apeout_swap {
         ctx: Context<ExecuteSwap>,
         amount_in: u64,
         minimum_amount_out: u64,
-        is_sol_to_token: bool
+        is_sol_to_token: bool,
+        deadline_unix_ts: Option<i64>,
+        max_price_impact_bps: Option<u16>
+    ) -> Result<()> {
+        require!(amount_in > 0, SwapError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline_unix_ts, now)?;
+
+        let pool_sol_bump = *ctx.bumps.get("pool_sol_account").unwrap();
+        let (mut core, mut routing, trader) = ctx.accounts.swap_parts(pool_sol_bump);
+
+        let swap = core.quote(SwapAmount::ExactIn(amount_in), is_sol_to_token, now)?;
+        require!(swap.amount_received >= minimum_amount_out, SwapError::SlippageExceeded);
+        core.execute(&mut routing, &trader, &swap, now, max_price_impact_bps)?;
+
+        msg!("Swap executed: {} in, {} out, {} fee", amount_in, swap.amount_received, swap.fee_amount);
+
+        Ok(())
+    }
+
+    /// Execute a swap for an exact output amount (SOL -> Token or Token -> SOL)
+    pub fn swap_exact_out(
+        ctx: Context<ExecuteSwap>,
+        amount_out: u64,
+        maximum_amount_in: u64,
+        is_sol_to_token: bool,
+        deadline_unix_ts: Option<i64>,
+        max_price_impact_bps: Option<u16>
+    ) -> Result<()> {
+        require!(amount_out > 0, SwapError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline_unix_ts, now)?;
+
+        let pool_sol_bump = *ctx.bumps.get("pool_sol_account").unwrap();
+        let (mut core, mut routing, trader) = ctx.accounts.swap_parts(pool_sol_bump);
+
+        let swap = core.quote(SwapAmount::ExactOut(amount_out), is_sol_to_token, now)?;
+        require!(swap.amount_in <= maximum_amount_in, SwapError::SlippageExceeded);
+        core.execute(&mut routing, &trader, &swap, now, max_price_impact_bps)?;
+
+        msg!("Swap executed: {} in, {} out, {} fee", swap.amount_in, amount_out, swap.fee_amount);
+
+        Ok(())
+    }
+
+    /// Swap one launched token for another, routing through both SOL pools atomically.
+    /// The SOL from the first hop passes through the user into the second.
+    pub fn swap_routed(
+        ctx: Context<SwapRouted>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        deadline_unix_ts: Option<i64>,
+        max_price_impact_bps: Option<u16>
     ) -> Result<()> {
         require!(amount_in > 0, SwapError::InvalidAmount);
+        require!(
+            ctx.accounts.pool_in.key() != ctx.accounts.pool_out.key(),
+            SwapError::InvalidRoute
+        );
+        require!(
+            ctx.accounts.pool_in.quote_mode == QuoteMode::NativeSol
+                && ctx.accounts.pool_out.quote_mode == QuoteMode::NativeSol,
+            SwapError::UnsupportedQuoteMode
+        );
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline_unix_ts, now)?;
+
+        let pool_in_sol_bump = *ctx.bumps.get("pool_in_sol_account").unwrap();
+        let pool_out_sol_bump = *ctx.bumps.get("pool_out_sol_account").unwrap();
+        let (mut hop_in, mut hop_out, mut routing, trader_in, trader_out) =
+            ctx.accounts.swap_parts(pool_in_sol_bump, pool_out_sol_bump);
+
+        // Hop 1: Token A -> SOL; hop 2: SOL -> Token B, spending exactly what hop 1 pays out
+        let sell = hop_in.quote(SwapAmount::ExactIn(amount_in), false, now)?;
+        require!(sell.amount_received > 0, SwapError::InvalidAmount);
+        let buy = hop_out.quote(SwapAmount::ExactIn(sell.amount_received), true, now)?;
+        require!(buy.amount_received >= minimum_amount_out, SwapError::SlippageExceeded);
+
+        // The impact limit applies to each hop
+        hop_in.execute(&mut routing, &trader_in, &sell, now, max_price_impact_bps)?;
+        hop_out.execute(&mut routing, &trader_out, &buy, now, max_price_impact_bps)?;
+
+        msg!("Routed swap executed: {} in, {} SOL routed, {} out, {} fee",
+             amount_in, sell.amount_received, buy.amount_received, math::add(sell.fee_amount, buy.fee_amount)?);
+
+        Ok(())
+    }
+
+    /// Execute several exact-input swaps all-or-nothing, e.g. buying a basket of
+    /// tokens. Each leg takes BATCH_LEG_ACCOUNTS remaining accounts, in the order of
+    /// `legs`, and is checked and settled like execute_swap; if any leg fails the
+    /// whole batch reverts. Native SOL pools only. Returns each leg's result.
+    pub fn batch_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchSwap<'info>>,
+        legs: Vec<BatchLeg>,
+        deadline_unix_ts: Option<i64>,
+        max_price_impact_bps: Option<u16>
+    ) -> Result<Vec<BatchLegResult>> {
+        require!(!legs.is_empty() && legs.len() <= MAX_BATCH_LEGS, SwapError::InvalidBatch);
+        require!(
+            ctx.remaining_accounts.len() == legs.len() * BATCH_LEG_ACCOUNTS,
+            SwapError::InvalidBatch
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline_unix_ts, now)?;
+
+        let mut results = Vec::with_capacity(legs.len());
+        for (leg, leg_accounts) in legs.iter().zip(ctx.remaining_accounts.chunks(BATCH_LEG_ACCOUNTS)) {
+            results.push(ctx.accounts.swap_leg(leg, leg_accounts, now, max_price_impact_bps)?);
+        }
+
+        msg!("Batch swap executed: {} legs", results.len());
+
+        Ok(results)
+    }
+
+    /// Get current token price in SOL
+    pub fn get_price(ctx: Context<GetPrice>) -> Result<u64> {
+        Ok(ctx.accounts.swap_pool.spot_price())
+    }
+
+    /// Get the fee rate in basis points a swap would pay right now
+    pub fn get_fee_rate(ctx: Context<GetPrice>) -> Result<u16> {
+        Ok(ctx.accounts.swap_pool.current_fee_rate(Clock::get()?.unix_timestamp))
+    }
+
+    /// Trading fees (lamports) earned by an LP position's share of the pool so far
+    pub fn get_lp_fees(ctx: Context<GetLpFees>) -> Result<u64> {
+        ctx.accounts.lp_position.fees_earned(ctx.accounts.lp_account.amount, ctx.accounts.swap_pool.fee_growth_per_share)
+    }
+
+    /// Get the time-weighted average price in SOL over at least `window_seconds`
+    pub fn get_twap(ctx: Context<GetPrice>, window_seconds: i64) -> Result<u64> {
+        require!(window_seconds > 0, SwapError::InvalidAmount);
+
+        ctx.accounts.swap_pool.twap(Clock::get()?.unix_timestamp, window_seconds)
+    }
+
+    /// Simulate an exact-input swap without moving funds. Prices and books the trade
+    /// the same way execute_swap does so clients can rely on it instead of
+    /// re-implementing the curve, and reports each guard that would reject it.
+    /// Without a buyer guard, launch limits are checked as the wallet's first buy.
+    pub fn quote_swap(
+        ctx: Context<QuoteSwap>,
+        amount_in: u64,
+        is_sol_to_token: bool
+    ) -> Result<SwapQuote> {
+        require!(amount_in > 0, SwapError::InvalidAmount);
+        let clock = Clock::get()?;
+
+        let swap_pool = &ctx.accounts.swap_pool;
+        require!(swap_pool.is_active, SwapError::PoolInactive);
+
+        // The same guards SwapCore::quote enforces
+        let (token_balance, sol_balance) = pool_balances(
+            swap_pool,
+            &ctx.accounts.pool_token_account,
+            &ctx.accounts.pool_sol_account,
+            ctx.accounts.pool_wsol_vault.as_ref(),
+        )?;
+        let blocked_by_launch_limit = is_sol_to_token && swap_pool.launch_protection_active(clock.unix_timestamp) && {
+            let (bought_in_slot, total_bought) = match &ctx.accounts.buyer_guard {
+                Some(guard) => launch_buy_totals(guard, clock.slot, amount_in)?,
+                None => (amount_in, amount_in),
+            };
+            !swap_pool.launch_protection.allows(bought_in_slot, total_bought)
+        };
+
+        let swap = price_trade(
+            swap_pool,
+            &ctx.accounts.token_mint.to_account_info(),
+            SwapAmount::ExactIn(amount_in),
+            is_sol_to_token,
+            clock.unix_timestamp,
+            clock.slot,
+        )?;
+
+        // Reserves as SwapCore::settle would leave them
+        let lp_fee = lp_fee_share(swap.fee_amount, &ctx.accounts.protocol_config)?;
+        let (sol_after, token_after) = swap_pool.reserves_after(&swap, lp_fee)?;
+        let post_trade_price = calculate_spot_price(sol_after, token_after);
+
+        Ok(SwapQuote {
+            amount_out: swap.amount_received,
+            fee_amount: swap.fee_amount,
+            fee_rate: swap_pool.current_fee_rate(clock.unix_timestamp),
+            price_impact_bps: price_impact_bps(swap.price_before, post_trade_price)?,
+            post_trade_price,
+            blocked_by_status: is_sol_to_token && ctx.accounts.project_tracker.status == TokenStatus::Dead,
+            blocked_by_lock: swap_pool.locked,
+            blocked_by_reserves: !swap_pool.reserves_in_sync(token_balance, sol_balance)?,
+            blocked_by_circuit_breaker: swap_pool.circuit_breaker_halts(clock.slot, is_sol_to_token),
+            blocked_by_launch_limit,
+        })
+    }
+
+    /// Flash swap: send `amount_out` of SOL or tokens to the borrower, invoke
+    /// `callback_program` with the remaining accounts and `callback_data`, then
+    /// require the pool to have been repaid so that the constant product, net of
+    /// the fee on whatever was paid in, is not lower than before. The pool stays
+    /// locked against swaps and liquidity changes while the callback runs. The fee
+    /// is taken in SOL and routed to fee_rewards like a swap fee.
+    pub fn flash_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, FlashSwap<'info>>,
+        amount_out: u64,
+        is_sol_out: bool,
+        callback_data: Vec<u8>
+    ) -> Result<()> {
+        require!(amount_out > 0, SwapError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
 
         // Extract values before borrowing issues
         let token_mint;
@@ -94,130 +547,474 @@ pub mod apeout_swap {
         let sol_reserve;
         let token_reserve;
         let fee_rate;
-        let _is_active;
-        
+
         {
             let swap_pool = &ctx.accounts.swap_pool;
             require!(swap_pool.is_active, SwapError::PoolInactive);
-            
+            require!(!swap_pool.locked, SwapError::PoolLocked);
+            require!(swap_pool.quote_mode == QuoteMode::NativeSol, SwapError::UnsupportedQuoteMode);
+
             token_mint = swap_pool.token_mint;
             bump = swap_pool.bump;
             sol_reserve = swap_pool.sol_reserve;
             token_reserve = swap_pool.token_reserve;
-            fee_rate = swap_pool.fee_rate;
-            _is_active = swap_pool.is_active;
+            fee_rate = swap_pool.current_fee_rate(now);
         }
 
-        let (amount_out, fee_amount) = if is_sol_to_token {
-            // SOL -> Token swap
-            let amount_out = calculate_swap_output(
-                amount_in,
-                sol_reserve,
-                token_reserve,
-                fee_rate
-            )?;
-            
-            require!(amount_out >= minimum_amount_out, SwapError::SlippageExceeded);
-            require!(amount_out <= token_reserve, SwapError::InsufficientLiquidity);
+        let (token_out, sol_out) = if is_sol_out { (0, amount_out) } else { (amount_out, 0) };
+        require!(token_out < token_reserve && sol_out < sol_reserve, SwapError::InsufficientLiquidity);
+
+        // Repayment is measured from the balances, so they must match the books first
+        let (token_balance_before, sol_balance_before) = pool_balances(
+            &ctx.accounts.swap_pool,
+            &ctx.accounts.pool_token_account,
+            &ctx.accounts.pool_sol_account,
+            None,
+        )?;
+        ctx.accounts.swap_pool.check_reserves(token_balance_before, sol_balance_before)?;
+
+        // Borrowing tokens pushes the price up like a buy, borrowing SOL down like a sell
+        let slot = Clock::get()?.slot;
+        let price_before = ctx.accounts.swap_pool.spot_price();
+        ctx.accounts.swap_pool.check_circuit_breaker(slot, !is_sol_out)?;
 
-            // Transfer SOL from user to pool
-            **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? -= amount_in;
-            **ctx.accounts.pool_sol_account.to_account_info().try_borrow_mut_lamports()? += amount_in;
+        // Lock the pool and write it out now, so instructions reentered from the
+        // callback see the lock. A failed flash swap reverts the lock with everything else.
+        ctx.accounts.swap_pool.update_price_accumulator(now);
+        ctx.accounts.swap_pool.locked = true;
+        ctx.accounts.swap_pool.exit(&crate::ID)?;
 
-            // Create seeds for signing
+        let pool_sol_bump = *ctx.bumps.get("pool_sol_account").unwrap();
+        if is_sol_out {
+            pay_from_pool_sol(
+                ctx.accounts.pool_sol_account.to_account_info(),
+                ctx.accounts.borrower.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                token_mint,
+                pool_sol_bump,
+                amount_out,
+            )?;
+        } else {
             let seeds = &[
                 b"swap_pool",
                 token_mint.as_ref(),
                 &[bump],
             ];
-
-            // Transfer tokens from pool to user
-            token::transfer(
+            token_interface::transfer_checked(
                 CpiContext::new_with_signer(
                     ctx.accounts.token_program.to_account_info(),
-                    Transfer {
+                    TransferChecked {
                         from: ctx.accounts.pool_token_account.to_account_info(),
-                        to: ctx.accounts.user_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.borrower_token_account.to_account_info(),
                         authority: ctx.accounts.swap_pool.to_account_info(),
                     },
                     &[seeds],
                 ),
                 amount_out,
+                ctx.accounts.token_mint.decimals,
             )?;
+        }
+
+        // Hand control to the borrower's program with the remaining accounts
+        let callback_metas = ctx.remaining_accounts
+            .iter()
+            .map(|account| if account.is_writable {
+                AccountMeta::new(account.key(), account.is_signer)
+            } else {
+                AccountMeta::new_readonly(account.key(), account.is_signer)
+            })
+            .collect();
+        let mut callback_infos = ctx.remaining_accounts.to_vec();
+        callback_infos.push(ctx.accounts.callback_program.to_account_info());
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::instruction::Instruction {
+                program_id: ctx.accounts.callback_program.key(),
+                accounts: callback_metas,
+                data: callback_data,
+            },
+            &callback_infos,
+        )?;
+
+        // New reserves from the actual balance changes, so Token-2022 transfer
+        // fees and anything sent straight to the pool are accounted for
+        ctx.accounts.pool_token_account.reload()?;
+        let new_token_reserve = math::sub(
+            math::add(token_reserve, ctx.accounts.pool_token_account.amount)?,
+            token_balance_before,
+        )?;
+        let new_sol_reserve = math::sub(
+            math::add(sol_reserve, ctx.accounts.pool_sol_account.lamports())?,
+            sol_balance_before,
+        )?;
+
+        // The fee is taken in SOL: the fee share of the SOL paid in plus the value of
+        // the fee share of the tokens paid in
+        let token_paid_in = new_token_reserve.saturating_sub(token_reserve - token_out);
+        let sol_paid_in = new_sol_reserve.saturating_sub(sol_reserve - sol_out);
+        let token_fee = math::mul_div_ceil(token_paid_in, fee_rate as u64, math::BPS_DENOMINATOR)?;
+        let fee_amount = math::add(
+            math::mul_div_ceil(sol_paid_in, fee_rate as u64, math::BPS_DENOMINATOR)?,
+            calculate_swap_output(token_fee, token_reserve, sol_reserve, 0)?,
+        )?;
+        let sol_after_fee = math::sub(new_sol_reserve, fee_amount)?;
+
+        // Constant product check with the fee paid out
+        require!(
+            new_token_reserve as u128 * sol_after_fee as u128 >= token_reserve as u128 * sol_reserve as u128,
+            SwapError::FlashSwapNotRepaid
+        );
+
+        let lp_fee = lp_fee_share(fee_amount, &ctx.accounts.protocol_config)?;
+
+        // Pick up anything the callback changed on the pool (e.g. disable_pool) before unlocking
+        ctx.accounts.swap_pool.reload()?;
+        let swap_pool = &mut ctx.accounts.swap_pool;
+        swap_pool.token_reserve = new_token_reserve;
+        swap_pool.sol_reserve = math::add(sol_after_fee, lp_fee)?;
+        swap_pool.locked = false;
+        swap_pool.accrue_fee(lp_fee);
+
+        // The breaker applies in the direction the loan and repayment moved the price
+        let price_after = swap_pool.spot_price();
+        swap_pool.check_circuit_breaker(slot, price_after >= price_before)?;
+        record_price_move(&mut ctx.accounts.swap_pool, slot, now)?;
 
-            (amount_out, amount_in * (fee_rate as u64) / 10000)
+        // Volume is the SOL value of the loan
+        let trade_volume = if is_sol_out {
+            amount_out
         } else {
-            // Token -> SOL swap
-            let amount_out = calculate_swap_output(
-                amount_in,
-                token_reserve,
-                sol_reserve,
-                fee_rate
-            )?;
+            calculate_swap_output(amount_out, token_reserve, sol_reserve, 0)?
+        };
+        record_trade_fee(
+            FeeAccounts {
+                rewards_pool: ctx.accounts.rewards_pool.to_account_info(),
+                daily_game_vault: ctx.accounts.daily_game_vault.to_account_info(),
+                platform_wallet: ctx.accounts.platform_wallet.to_account_info(),
+                fee_payer: ctx.accounts.pool_sol_account.to_account_info(),
+                token_mint: ctx.accounts.token_mint.to_account_info(),
+                protocol_config: ctx.accounts.protocol_config.to_account_info(),
+                fee_authority: ctx.accounts.fee_authority.to_account_info(),
+                fee_rewards_program: ctx.accounts.fee_rewards_program.to_account_info(),
+                daily_game_vault_program: ctx.accounts.daily_game_vault_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            token_mint,
+            pool_sol_bump,
+            math::sub(fee_amount, lp_fee)?,
+            trade_volume,
+        )?;
+
+        msg!("Flash swap: {} {} out, repaid {} SOL and {} tokens, {} fee",
+             amount_out, if is_sol_out { "SOL" } else { "tokens" }, sol_paid_in, token_paid_in, fee_amount);
+        Ok(())
+    }
 
-            require!(amount_out >= minimum_amount_out, SwapError::SlippageExceeded);
-            require!(amount_out <= sol_reserve, SwapError::InsufficientLiquidity);
+    /// Place a limit order: escrow SOL (buy) or tokens (sell) plus a filler bounty
+    pub fn place_limit_order(ctx: Context<PlaceLimitOrder>, params: OrderParams) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(params.amount_in > 0 && params.target_price > 0, SwapError::InvalidAmount);
+        require!(params.expiry > now && params.twap_window >= 0, SwapError::InvalidOrder);
+        require!(ctx.accounts.swap_pool.is_active, SwapError::PoolInactive);
+        require!(ctx.accounts.swap_pool.quote_mode == QuoteMode::NativeSol, SwapError::UnsupportedQuoteMode);
 
-            // Transfer tokens from user to pool
-            token::transfer(
+        // Buys escrow SOL in the order account itself; sells escrow tokens in the
+        // order's token account, net of any Token-2022 transfer fee
+        let (escrowed, lamports_in) = if params.is_sol_to_token {
+            (params.amount_in, math::add(params.amount_in, params.filler_bounty)?)
+        } else {
+            token_interface::transfer_checked(
                 CpiContext::new(
                     ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.user_token_account.to_account_info(),
-                        to: ctx.accounts.pool_token_account.to_account_info(),
-                        authority: ctx.accounts.user.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.owner_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.escrow_token_account.to_account_info(),
+                        authority: ctx.accounts.owner.to_account_info(),
                     },
                 ),
-                amount_in,
+                params.amount_in,
+                ctx.accounts.token_mint.decimals,
             )?;
+            let fee = transfer_fee(&ctx.accounts.token_mint.to_account_info(), params.amount_in)?;
+            (math::sub(params.amount_in, fee)?, params.filler_bounty)
+        };
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.order.to_account_info(),
+                },
+            ),
+            lamports_in,
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.owner = ctx.accounts.owner.key();
+        order.swap_pool = ctx.accounts.swap_pool.key();
+        order.order_id = params.order_id;
+        order.is_sol_to_token = params.is_sol_to_token;
+        order.amount_in = escrowed;
+        order.min_amount_out = params.min_amount_out;
+        order.target_price = params.target_price;
+        order.twap_window = params.twap_window;
+        order.expiry = params.expiry;
+        order.filler_bounty = params.filler_bounty;
+        order.created_at = now;
+        order.bump = *ctx.bumps.get("order").unwrap();
+
+        msg!("Limit order {} placed: {} in at target price {}", order.order_id, escrowed, order.target_price);
+        Ok(())
+    }
+
+    /// Fill a limit order once the pool price crosses its target (permissionless).
+    /// The filler receives the order's bounty.
+    pub fn fill_order(ctx: Context<FillOrder>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
 
-            // Transfer SOL from pool to user
-            **ctx.accounts.pool_sol_account.to_account_info().try_borrow_mut_lamports()? -= amount_out;
-            **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += amount_out;
+        // Extract values before borrowing issues
+        let order_key = ctx.accounts.order.key();
+        let owner = ctx.accounts.order.owner;
+        let order_id = ctx.accounts.order.order_id;
+        let order_bump = ctx.accounts.order.bump;
+        let is_sol_to_token = ctx.accounts.order.is_sol_to_token;
+        let amount_in = ctx.accounts.order.amount_in;
+        let min_amount_out = ctx.accounts.order.min_amount_out;
+        let target_price = ctx.accounts.order.target_price;
+        let twap_window = ctx.accounts.order.twap_window;
+        let filler_bounty = ctx.accounts.order.filler_bounty;
+        require!(now <= ctx.accounts.order.expiry, SwapError::OrderExpired);
+        require!(ctx.accounts.swap_pool.quote_mode == QuoteMode::NativeSol, SwapError::UnsupportedQuoteMode);
 
-            (amount_out, amount_in * (fee_rate as u64) / 10000)
+        let trigger_price = if twap_window > 0 {
+            ctx.accounts.swap_pool.twap(now, twap_window)?
+        } else {
+            ctx.accounts.swap_pool.spot_price()
         };
 
-        // Update reserves AFTER all token operations
-        let swap_pool = &mut ctx.accounts.swap_pool;
+        // Sells trigger at or above the target, buys at or below
         if is_sol_to_token {
-            swap_pool.sol_reserve += amount_in;
-            swap_pool.token_reserve -= amount_out;
+            require!(trigger_price <= target_price, SwapError::OrderNotTriggered);
         } else {
-            swap_pool.token_reserve += amount_in;
-            swap_pool.sol_reserve -= amount_out;
+            require!(trigger_price >= target_price, SwapError::OrderNotTriggered);
         }
 
-        msg!("Swap executed: {} in, {} out, {} fee", amount_in, amount_out, fee_amount);
+        // The order signs for the escrowed tokens of a sell
+        let order_id_bytes = order_id.to_le_bytes();
+        let order_bump_bytes = [order_bump];
+        let order_seeds: &[&[u8]] = &[
+            b"limit_order",
+            owner.as_ref(),
+            &order_id_bytes,
+            &order_bump_bytes,
+        ];
+
+        let pool_sol_bump = *ctx.bumps.get("pool_sol_account").unwrap();
+        let (mut core, mut routing, mut trader) = ctx.accounts.swap_parts(pool_sol_bump, is_sol_to_token);
+        trader.authority_seeds = Some(order_seeds);
 
+        let swap = core.quote(SwapAmount::ExactIn(amount_in), is_sol_to_token, now)?;
+        require!(swap.amount_received >= min_amount_out, SwapError::SlippageExceeded);
+        core.execute(&mut routing, &trader, &swap, now, None)?;
+
+        // Pay the filler; the order account is closed to the owner afterwards
+        **ctx.accounts.order.to_account_info().try_borrow_mut_lamports()? -= filler_bounty;
+        **ctx.accounts.filler.to_account_info().try_borrow_mut_lamports()? += filler_bounty;
+
+        ctx.accounts.close_escrow(owner, order_id, order_bump)?;
+
+        msg!("Limit order {} filled: {} in, {} out, {} fee, {} bounty",
+             order_key, amount_in, swap.amount_received, swap.fee_amount, filler_bounty);
         Ok(())
     }
 
-    /// Get current token price in SOL
-    pub fn get_price(ctx: Context<GetPrice>) -> Result<u64> {
-        let swap_pool = &ctx.accounts.swap_pool;
-        
-        if swap_pool.token_reserve == 0 {
-            return Ok(0);
+    /// Cancel a limit order and refund the escrow and bounty to the owner
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        // Extract values before borrowing issues
+        let owner = ctx.accounts.order.owner;
+        let order_id = ctx.accounts.order.order_id;
+        let order_bump = ctx.accounts.order.bump;
+        let escrowed_tokens = ctx.accounts.escrow_token_account.amount;
+
+        let order_seeds = &[
+            b"limit_order",
+            owner.as_ref(),
+            &order_id.to_le_bytes(),
+            &[order_bump],
+        ];
+
+        // Sell orders hold tokens; buy orders get their SOL back when the order closes
+        if escrowed_tokens > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.escrow_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.owner_token_account.to_account_info(),
+                        authority: ctx.accounts.order.to_account_info(),
+                    },
+                    &[order_seeds],
+                ),
+                escrowed_tokens,
+                ctx.accounts.token_mint.decimals,
+            )?;
         }
 
-        // Price = SOL reserve / Token reserve (scaled by 1e9 for precision)
-        let price = (swap_pool.sol_reserve as u128 * 1_000_000_000) / swap_pool.token_reserve as u128;
-        
-        Ok(price as u64)
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.owner.to_account_info(),
+                authority: ctx.accounts.order.to_account_info(),
+            },
+            &[order_seeds],
+        ))?;
+
+        msg!("Limit order {} cancelled", order_id);
+        Ok(())
     }
 
-    /// Add liquidity to existing pool (for future use)
-    pub fn add_liquidity(
-        ctx: Context<AddLiquidity>,
-        token_amount: u64,
-        sol_amount: u64,
-        min_lp_tokens: u64
-    ) -> Result<()> {
-        require!(token_amount > 0 && sol_amount > 0, SwapError::InvalidAmount);
+    /// Open a DCA vault: deposit SOL for `intervals` buys of `amount_per_interval`,
+    /// plus a crank bounty for each. The first slice can execute right away.
+    pub fn open_dca_vault(ctx: Context<OpenDcaVault>, params: DcaParams) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(params.amount_per_interval > 0 && params.max_price > 0, SwapError::InvalidAmount);
+        require!(params.interval > 0 && params.intervals > 0, SwapError::InvalidDcaSchedule);
+        require!(ctx.accounts.swap_pool.is_active, SwapError::PoolInactive);
+        require!(ctx.accounts.swap_pool.quote_mode == QuoteMode::NativeSol, SwapError::UnsupportedQuoteMode);
+
+        // The vault account holds the SOL for every slice and its bounty
+        let per_slice = math::add(params.amount_per_interval, params.crank_bounty)?;
+        let deposit = per_slice.checked_mul(params.intervals as u64).ok_or(SwapError::MathOverflow)?;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.dca_vault.to_account_info(),
+                },
+            ),
+            deposit,
+        )?;
+
+        let dca_vault = &mut ctx.accounts.dca_vault;
+        dca_vault.owner = ctx.accounts.owner.key();
+        dca_vault.swap_pool = ctx.accounts.swap_pool.key();
+        dca_vault.vault_id = params.vault_id;
+        dca_vault.amount_per_interval = params.amount_per_interval;
+        dca_vault.interval = params.interval;
+        dca_vault.intervals_total = params.intervals;
+        dca_vault.intervals_done = 0;
+        dca_vault.max_price = params.max_price;
+        dca_vault.crank_bounty = params.crank_bounty;
+        dca_vault.next_execution_at = now;
+        dca_vault.sol_spent = 0;
+        dca_vault.tokens_bought = 0;
+        dca_vault.created_at = now;
+        dca_vault.bump = *ctx.bumps.get("dca_vault").unwrap();
+
+        msg!("DCA vault {} opened: {} x {} lamports every {}s",
+             dca_vault.vault_id, params.intervals, params.amount_per_interval, params.interval);
+        Ok(())
+    }
+
+    /// Execute the next due slice of a DCA vault (permissionless). The slice buys
+    /// like execute_swap and must fill at an average price no higher than the
+    /// vault's max_price; tokens stay in the vault's token account. The cranker
+    /// receives the vault's crank bounty.
+    pub fn execute_dca_slice(ctx: Context<ExecuteDcaSlice>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
 
         // Extract values before borrowing issues
-        let token_mint;
+        let dca_key = ctx.accounts.dca_vault.key();
+        let amount_in = ctx.accounts.dca_vault.amount_per_interval;
+        let max_price = ctx.accounts.dca_vault.max_price;
+        let crank_bounty = ctx.accounts.dca_vault.crank_bounty;
+        require!(
+            ctx.accounts.dca_vault.intervals_done < ctx.accounts.dca_vault.intervals_total,
+            SwapError::DcaComplete
+        );
+        require!(now >= ctx.accounts.dca_vault.next_execution_at, SwapError::DcaSliceNotDue);
+        require!(ctx.accounts.swap_pool.quote_mode == QuoteMode::NativeSol, SwapError::UnsupportedQuoteMode);
+
+        let pool_sol_bump = *ctx.bumps.get("pool_sol_account").unwrap();
+        let (mut core, mut routing, trader) = ctx.accounts.swap_parts(pool_sol_bump);
+
+        let swap = core.quote(SwapAmount::ExactIn(amount_in), true, now)?;
+
+        // Slippage bound: SOL paid per token received (1e9 scaled) can't exceed max_price
+        let min_amount_out = math::mul_div_ceil(amount_in, 1_000_000_000, max_price)?;
+        require!(swap.amount_received >= min_amount_out, SwapError::DcaPriceAboveMax);
+
+        core.execute(&mut routing, &trader, &swap, now, None)?;
+
+        // Pay the cranker out of the vault's escrowed bounties
+        **ctx.accounts.dca_vault.to_account_info().try_borrow_mut_lamports()? -= crank_bounty;
+        **ctx.accounts.cranker.to_account_info().try_borrow_mut_lamports()? += crank_bounty;
+
+        let dca_vault = &mut ctx.accounts.dca_vault;
+        dca_vault.intervals_done += 1;
+        dca_vault.next_execution_at = now.saturating_add(dca_vault.interval);
+        dca_vault.sol_spent = math::add(dca_vault.sol_spent, amount_in)?;
+        dca_vault.tokens_bought = math::add(dca_vault.tokens_bought, swap.amount_received)?;
+        let intervals_done = dca_vault.intervals_done;
+        let intervals_total = dca_vault.intervals_total;
+
+        msg!("DCA vault {} slice {}/{}: {} in, {} out, {} fee, {} bounty",
+             dca_key, intervals_done, intervals_total, amount_in, swap.amount_received, swap.fee_amount, crank_bounty);
+        Ok(())
+    }
+
+    /// Withdraw the tokens a DCA vault has bought so far (owner only)
+    pub fn withdraw_dca_tokens(ctx: Context<WithdrawDcaTokens>) -> Result<()> {
+        let amount = ctx.accounts.dca_token_account.amount;
+        require!(amount > 0, SwapError::InvalidAmount);
+
+        ctx.accounts.transfer_tokens_to_owner(amount)?;
+
+        msg!("Withdrew {} tokens from DCA vault {}", amount, ctx.accounts.dca_vault.vault_id);
+        Ok(())
+    }
+
+    /// Cancel a DCA vault: return its bought tokens and close it, refunding the
+    /// SOL of unexecuted slices and their bounties to the owner
+    pub fn cancel_dca_vault(ctx: Context<WithdrawDcaTokens>) -> Result<()> {
+        // Extract values before borrowing issues
+        let vault_id = ctx.accounts.dca_vault.vault_id;
+        let intervals_done = ctx.accounts.dca_vault.intervals_done;
+        let intervals_total = ctx.accounts.dca_vault.intervals_total;
+        let amount = ctx.accounts.dca_token_account.amount;
+
+        if amount > 0 {
+            ctx.accounts.transfer_tokens_to_owner(amount)?;
+        }
+        ctx.accounts.close_token_account()?;
+        ctx.accounts.dca_vault.close(ctx.accounts.owner.to_account_info())?;
+
+        msg!("DCA vault {} cancelled after {}/{} slices", vault_id, intervals_done, intervals_total);
+        Ok(())
+    }
+
+    /// Add liquidity to existing pool. `max_price_impact_bps` bounds how far the
+    /// deposit ratio may be from the pool's spot price.
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        token_amount: u64,
+        sol_amount: u64,
+        min_lp_tokens: u64,
+        deadline_unix_ts: Option<i64>,
+        max_price_impact_bps: Option<u16>
+    ) -> Result<()> {
+        require!(token_amount > 0 && sol_amount > 0, SwapError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline_unix_ts, now)?;
+        let lp_balance_before = ctx.accounts.user_lp_account.amount;
+
+        // Extract values before borrowing issues
+        let token_mint;
         let bump;
         let total_lp_supply;
         let token_reserve;
@@ -226,6 +1023,7 @@ pub mod apeout_swap {
         {
             let swap_pool = &ctx.accounts.swap_pool;
             require!(swap_pool.is_active, SwapError::PoolInactive);
+            require!(!swap_pool.locked, SwapError::PoolLocked);
             
             token_mint = swap_pool.token_mint;
             bump = swap_pool.bump;
@@ -234,33 +1032,76 @@ pub mod apeout_swap {
             sol_reserve = swap_pool.sol_reserve;
         }
 
-        // Calculate LP tokens to mint based on current reserves
-        let lp_tokens = if total_lp_supply == 0 {
-            (token_amount as f64 * sol_amount as f64).sqrt() as u64
+        // Only what reaches the pool after any Token-2022 transfer fee counts as liquidity
+        let token_deposit = math::sub(token_amount, transfer_fee(&ctx.accounts.token_mint.to_account_info(), token_amount)?)?;
+
+        if total_lp_supply > 0 {
+            check_price_impact(
+                calculate_spot_price(sol_reserve, token_reserve),
+                calculate_spot_price(sol_amount, token_deposit),
+                max_price_impact_bps,
+            )?;
+        }
+
+        // Calculate LP tokens to mint based on current reserves. An empty pool is
+        // seeded like init_swap_pool, locking MINIMUM_LIQUIDITY.
+        let (lp_supply_added, lp_tokens) = if total_lp_supply == 0 {
+            math::initial_lp_supply(token_deposit, sol_amount)?
         } else {
-            std::cmp::min(
-                (token_amount as u128 * total_lp_supply as u128 / token_reserve as u128) as u64,
-                (sol_amount as u128 * total_lp_supply as u128 / sol_reserve as u128) as u64,
-            )
+            let lp_tokens = std::cmp::min(
+                math::mul_div(token_deposit, total_lp_supply, token_reserve)?,
+                math::mul_div(sol_amount, total_lp_supply, sol_reserve)?,
+            );
+            (lp_tokens, lp_tokens)
         };
 
         require!(lp_tokens >= min_lp_tokens, SwapError::SlippageExceeded);
 
         // Transfer tokens and SOL
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
                     to: ctx.accounts.pool_token_account.to_account_info(),
                     authority: ctx.accounts.user.to_account_info(),
                 },
             ),
             token_amount,
+            ctx.accounts.token_mint.decimals,
         )?;
 
-        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? -= sol_amount;
-        **ctx.accounts.pool_sol_account.to_account_info().try_borrow_mut_lamports()? += sol_amount;
+        match ctx.accounts.swap_pool.quote_mode {
+            QuoteMode::NativeSol => {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.user.to_account_info(),
+                            to: ctx.accounts.pool_sol_account.to_account_info(),
+                        },
+                    ),
+                    sol_amount,
+                )?;
+            }
+            QuoteMode::WrappedSol => {
+                let (pool_wsol_vault, wsol_token_program) = match (
+                    &ctx.accounts.pool_wsol_vault,
+                    &ctx.accounts.wsol_token_program,
+                ) {
+                    (Some(vault), Some(program)) => (vault.to_account_info(), program.to_account_info()),
+                    _ => return Err(SwapError::MissingWsolAccounts.into()),
+                };
+                wrap_sol(
+                    ctx.accounts.user.to_account_info(),
+                    pool_wsol_vault,
+                    ctx.accounts.system_program.to_account_info(),
+                    wsol_token_program,
+                    sol_amount,
+                )?;
+            }
+        }
 
         // Create seeds for signing
         let seeds = &[
@@ -270,7 +1111,7 @@ pub mod apeout_swap {
         ];
 
         // Mint LP tokens to user
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
@@ -285,24 +1126,526 @@ pub mod apeout_swap {
 
         // Update reserves and supply
         let swap_pool = &mut ctx.accounts.swap_pool;
-        swap_pool.token_reserve += token_amount;
-        swap_pool.sol_reserve += sol_amount;
-        swap_pool.total_lp_supply += lp_tokens;
+        swap_pool.update_price_accumulator(now);
+        swap_pool.token_reserve = math::add(swap_pool.token_reserve, token_deposit)?;
+        swap_pool.sol_reserve = math::add(swap_pool.sol_reserve, sol_amount)?;
+        swap_pool.total_lp_supply = math::add(swap_pool.total_lp_supply, lp_supply_added)?;
+
+        // SOL-equivalent value of the deposit: the SOL plus the tokens at the pool price
+        let token_value = if token_reserve > 0 {
+            math::mul_div(token_deposit, sol_reserve, token_reserve)?
+        } else {
+            sol_amount
+        };
+        let sol_value = math::add(sol_amount, token_value)?;
+
+        // Fees up to now are earned on the LP balance held before the mint
+        let fee_growth = swap_pool.fee_growth_per_share;
+        if let Some(position) = &mut ctx.accounts.lp_position {
+            position.settle(lp_balance_before, fee_growth)?;
+            position.sol_value_added = math::add(position.sol_value_added, sol_value)?;
+        }
+
+        ctx.accounts.record_lp_provision(token_mint, bump, sol_value)?;
+
+        let swap_pool = &ctx.accounts.swap_pool;
+        emit!(LiquidityAdded {
+            swap_pool: swap_pool.key(),
+            token_mint,
+            provider: ctx.accounts.user.key(),
+            token_amount: token_deposit,
+            sol_amount,
+            lp_tokens,
+            timestamp: now,
+        });
 
         msg!("Liquidity added: {} tokens, {} SOL, {} LP tokens", 
-             token_amount, sol_amount, lp_tokens);
+             token_deposit, sol_amount, lp_tokens);
+
+        Ok(())
+    }
+
+    /// Remove liquidity by burning LP tokens for a pro-rata share of the reserves.
+    /// Withdrawing doesn't move the price, so only a deadline applies.
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        lp_amount: u64,
+        min_token_out: u64,
+        min_sol_out: u64,
+        deadline_unix_ts: Option<i64>
+    ) -> Result<()> {
+        require!(lp_amount > 0, SwapError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
+        check_deadline(deadline_unix_ts, now)?;
+        let lp_balance_before = ctx.accounts.user_lp_account.amount;
+
+        // Extract values before borrowing issues
+        let token_mint;
+        let bump;
+        let total_lp_supply;
+        let token_reserve;
+        let sol_reserve;
+
+        {
+            let swap_pool = &ctx.accounts.swap_pool;
+            require!(!swap_pool.locked, SwapError::PoolLocked);
+
+            token_mint = swap_pool.token_mint;
+            bump = swap_pool.bump;
+            total_lp_supply = swap_pool.total_lp_supply;
+            token_reserve = swap_pool.token_reserve;
+            sol_reserve = swap_pool.sol_reserve;
+        }
+
+        require!(lp_amount <= total_lp_supply, SwapError::InsufficientLiquidity);
+
+        // Pro-rata share of each reserve, rounded down in the pool's favour
+        let token_out = math::mul_div(lp_amount, token_reserve, total_lp_supply)?;
+        let sol_out = math::mul_div(lp_amount, sol_reserve, total_lp_supply)?;
+
+        require!(token_out > 0 || sol_out > 0, SwapError::InvalidAmount);
+
+        // Slippage applies to what the user receives after any Token-2022 transfer fee
+        let token_received = math::sub(token_out, transfer_fee(&ctx.accounts.token_mint.to_account_info(), token_out)?)?;
+        require!(
+            token_received >= min_token_out && sol_out >= min_sol_out,
+            SwapError::SlippageExceeded
+        );
+
+        // Burn LP tokens from the caller
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.user_lp_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
+
+        // Create seeds for signing
+        let seeds = &[
+            b"swap_pool",
+            token_mint.as_ref(),
+            &[bump],
+        ];
+
+        // Transfer tokens from pool to user
+        if token_out > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.pool_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.user_token_account.to_account_info(),
+                        authority: ctx.accounts.swap_pool.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                token_out,
+                ctx.accounts.token_mint.decimals,
+            )?;
+        }
+
+        // Transfer SOL from pool to user. Wrapped-SOL pools pay out WSOL, which the
+        // user (or lp_custody) unwraps by closing the receiving account.
+        match ctx.accounts.swap_pool.quote_mode {
+            QuoteMode::NativeSol => {
+                let pool_sol_bump = *ctx.bumps.get("pool_sol_account").unwrap();
+                pay_from_pool_sol(
+                    ctx.accounts.pool_sol_account.to_account_info(),
+                    ctx.accounts.user.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    token_mint,
+                    pool_sol_bump,
+                    sol_out,
+                )?;
+            }
+            QuoteMode::WrappedSol => {
+                let (pool_wsol_vault, user_wsol_account, wsol_token_program) = match (
+                    &ctx.accounts.pool_wsol_vault,
+                    &ctx.accounts.user_wsol_account,
+                    &ctx.accounts.wsol_token_program,
+                ) {
+                    (Some(vault), Some(user_wsol), Some(program)) => (
+                        vault.to_account_info(),
+                        user_wsol.to_account_info(),
+                        program.to_account_info(),
+                    ),
+                    _ => return Err(SwapError::MissingWsolAccounts.into()),
+                };
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        wsol_token_program,
+                        token::Transfer {
+                            from: pool_wsol_vault,
+                            to: user_wsol_account,
+                            authority: ctx.accounts.swap_pool.to_account_info(),
+                        },
+                        &[seeds],
+                    ),
+                    sol_out,
+                )?;
+            }
+        }
+
+        // Update reserves and supply
+        let swap_pool = &mut ctx.accounts.swap_pool;
+        swap_pool.update_price_accumulator(now);
+        swap_pool.token_reserve = math::sub(swap_pool.token_reserve, token_out)?;
+        swap_pool.sol_reserve = math::sub(swap_pool.sol_reserve, sol_out)?;
+        swap_pool.total_lp_supply = math::sub(swap_pool.total_lp_supply, lp_amount)?;
+
+        // Fees up to now are earned on the LP balance held before the burn
+        let fee_growth = swap_pool.fee_growth_per_share;
+        if let Some(position) = &mut ctx.accounts.lp_position {
+            position.settle(lp_balance_before, fee_growth)?;
+        }
+
+        let swap_pool = &ctx.accounts.swap_pool;
+        emit!(LiquidityRemoved {
+            swap_pool: swap_pool.key(),
+            token_mint,
+            provider: ctx.accounts.user.key(),
+            token_amount: token_out,
+            sol_amount: sol_out,
+            lp_tokens: lp_amount,
+            timestamp: now,
+        });
+
+        msg!("Liquidity removed: {} LP tokens burned for {} tokens, {} SOL",
+             lp_amount, token_out, sol_out);
 
         Ok(())
     }
 
-    /// Disable pool (called when token dies)
+    /// Disable pool (called by lp_custody when token dies, or by the protocol admin)
     pub fn disable_pool(ctx: Context<DisablePool>) -> Result<()> {
         let swap_pool = &mut ctx.accounts.swap_pool;
         swap_pool.is_active = false;
+
+        emit!(PoolDisabled {
+            swap_pool: swap_pool.key(),
+            token_mint: swap_pool.token_mint,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         
         msg!("Pool disabled for token {}", swap_pool.token_mint);
         Ok(())
     }
+
+    /// Configure the pool's price circuit breaker (admin only). `max_move_bps` of
+    /// zero turns it off; `window_slots` of zero limits moves within a single slot.
+    pub fn configure_circuit_breaker(
+        ctx: Context<ConfigurePool>,
+        max_move_bps: u16,
+        window_slots: u64
+    ) -> Result<()> {
+        let swap_pool = &mut ctx.accounts.swap_pool;
+        swap_pool.circuit_breaker = CircuitBreaker {
+            max_move_bps,
+            window_slots,
+            ..CircuitBreaker::default()
+        };
+
+        msg!("Circuit breaker for {}: {} bps per {} slots",
+             swap_pool.token_mint, max_move_bps, window_slots.max(1));
+        Ok(())
+    }
+
+    /// Grow a pool created with an older layout to SwapPool::LEN (permissionless;
+    /// the payer funds the extra rent). Appended fields start zeroed, except the
+    /// fee schedule, which is taken from the protocol config.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let pool_info = ctx.accounts.swap_pool.to_account_info();
+        let old_len = pool_info.data_len();
+        require!(old_len < SwapPool::LEN, SwapError::PoolUpToDate);
+
+        // Only a swap pool PDA of this program can be grown
+        {
+            let data = pool_info.try_borrow_data()?;
+            require!(
+                pool_info.owner == &crate::ID && old_len >= 8 + 32 && data[..8] == SwapPool::DISCRIMINATOR,
+                SwapError::InvalidPoolAccount
+            );
+            let token_mint = Pubkey::try_from(&data[8..40]).map_err(|_| SwapError::InvalidPoolAccount)?;
+            let (expected, _) = Pubkey::find_program_address(&[b"swap_pool", token_mint.as_ref()], &crate::ID);
+            require!(pool_info.key() == expected, SwapError::InvalidPoolAccount);
+        }
+
+        let rent_due = Rent::get()?.minimum_balance(SwapPool::LEN).saturating_sub(pool_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: pool_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        pool_info.realloc(SwapPool::LEN, true)?;
+
+        let mut swap_pool = Account::<SwapPool>::try_from(&pool_info)?;
+        swap_pool.fee_schedule = FeeSchedule::from_config(&ctx.accounts.protocol_config);
+        swap_pool.exit(&crate::ID)?;
+
+        msg!("Pool {} migrated: {} -> {} bytes", swap_pool.token_mint, old_len, SwapPool::LEN);
+        Ok(())
+    }
+
+    /// Copy the protocol's current dynamic fee bounds and premiums into the pool
+    /// (permissionless), so governance changes reach existing pools
+    pub fn refresh_fee_schedule(ctx: Context<RefreshFeeSchedule>) -> Result<()> {
+        let swap_pool = &mut ctx.accounts.swap_pool;
+        swap_pool.fee_schedule = FeeSchedule::from_config(&ctx.accounts.protocol_config);
+
+        msg!("Fee schedule for {}: {}-{} bps", swap_pool.token_mint,
+             swap_pool.fee_schedule.min_fee_rate, swap_pool.fee_schedule.max_fee_rate);
+        Ok(())
+    }
+
+    /// Set the booked reserves to the pool's actual balances (permissionless).
+    /// Donations go to the LPs; shortfalls are written off against them.
+    pub fn sync(ctx: Context<SyncReserves>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(!ctx.accounts.swap_pool.locked, SwapError::PoolLocked);
+
+        let (token_balance, sol_balance) = pool_balances(
+            &ctx.accounts.swap_pool,
+            &ctx.accounts.pool_token_account,
+            &ctx.accounts.pool_sol_account,
+            ctx.accounts.pool_wsol_vault.as_ref(),
+        )?;
+
+        let swap_pool = &mut ctx.accounts.swap_pool;
+        swap_pool.update_price_accumulator(now);
+        swap_pool.token_reserve = token_balance;
+        swap_pool.sol_reserve = sol_balance;
+
+        emit!(ReservesSynced {
+            swap_pool: swap_pool.key(),
+            token_mint: swap_pool.token_mint,
+            token_reserve: token_balance,
+            sol_reserve: sol_balance,
+            timestamp: now,
+        });
+
+        msg!("Reserves synced: {} tokens, {} SOL", token_balance, sol_balance);
+        Ok(())
+    }
+
+    /// Send any balance above the booked reserves to the platform treasury (permissionless)
+    pub fn skim(ctx: Context<SkimReserves>) -> Result<()> {
+        // Extract values before borrowing issues
+        let token_mint;
+        let bump;
+        let token_reserve;
+        let sol_reserve;
+        let quote_mode;
+
+        {
+            let swap_pool = &ctx.accounts.swap_pool;
+            require!(!swap_pool.locked, SwapError::PoolLocked);
+
+            token_mint = swap_pool.token_mint;
+            bump = swap_pool.bump;
+            token_reserve = swap_pool.token_reserve;
+            sol_reserve = swap_pool.sol_reserve;
+            quote_mode = swap_pool.quote_mode;
+        }
+
+        let (token_balance, sol_balance) = pool_balances(
+            &ctx.accounts.swap_pool,
+            &ctx.accounts.pool_token_account,
+            &ctx.accounts.pool_sol_account,
+            ctx.accounts.pool_wsol_vault.as_ref(),
+        )?;
+        let token_excess = token_balance.saturating_sub(token_reserve);
+        let sol_excess = sol_balance.saturating_sub(sol_reserve);
+
+        // Create seeds for signing
+        let seeds = &[
+            b"swap_pool",
+            token_mint.as_ref(),
+            &[bump],
+        ];
+
+        if token_excess > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.pool_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.treasury_token_account.to_account_info(),
+                        authority: ctx.accounts.swap_pool.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                token_excess,
+                ctx.accounts.token_mint.decimals,
+            )?;
+        }
+
+        if sol_excess > 0 {
+            match quote_mode {
+                QuoteMode::NativeSol => pay_from_pool_sol(
+                    ctx.accounts.pool_sol_account.to_account_info(),
+                    ctx.accounts.platform_treasury.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    token_mint,
+                    *ctx.bumps.get("pool_sol_account").unwrap(),
+                    sol_excess,
+                )?,
+                QuoteMode::WrappedSol => {
+                    let (pool_wsol_vault, treasury_wsol_account, wsol_token_program) = match (
+                        &ctx.accounts.pool_wsol_vault,
+                        &ctx.accounts.treasury_wsol_account,
+                        &ctx.accounts.wsol_token_program,
+                    ) {
+                        (Some(vault), Some(treasury), Some(program)) => (vault, treasury, program),
+                        _ => return Err(SwapError::MissingWsolAccounts.into()),
+                    };
+
+                    token::transfer(
+                        CpiContext::new_with_signer(
+                            wsol_token_program.to_account_info(),
+                            token::Transfer {
+                                from: pool_wsol_vault.to_account_info(),
+                                to: treasury_wsol_account.to_account_info(),
+                                authority: ctx.accounts.swap_pool.to_account_info(),
+                            },
+                            &[seeds],
+                        ),
+                        sol_excess,
+                    )?;
+                }
+            }
+        }
+
+        msg!("Skimmed {} tokens and {} SOL to the treasury", token_excess, sol_excess);
+        Ok(())
+    }
+}
+
+// Helper function to read a pool's actual token and SOL balances. The SOL side is
+// the pool_sol lamports for native pools and the WSOL vault balance otherwise.
+fn pool_balances(
+    swap_pool: &SwapPool,
+    pool_token_account: &InterfaceAccount<TokenAccount>,
+    pool_sol_account: &AccountInfo,
+    pool_wsol_vault: Option<&InterfaceAccount<TokenAccount>>
+) -> Result<(u64, u64)> {
+    let sol_balance = match swap_pool.quote_mode {
+        QuoteMode::NativeSol => pool_sol_account.lamports(),
+        QuoteMode::WrappedSol => pool_wsol_vault.ok_or(SwapError::MissingWsolAccounts)?.amount,
+    };
+
+    Ok((pool_token_account.amount, sol_balance))
+}
+
+// Helper function to price a swap against a pool's books. SwapCore::quote and
+// quote_swap both price through here so quotes match execution.
+fn price_trade(
+    swap_pool: &SwapPool,
+    mint_info: &AccountInfo,
+    amount: SwapAmount,
+    is_sol_to_token: bool,
+    now: i64,
+    slot: u64
+) -> Result<PricedSwap> {
+    let sol_reserve = swap_pool.sol_reserve;
+    let token_reserve = swap_pool.token_reserve;
+    let fee_rate = swap_pool.current_fee_rate(now);
+
+    let (amount_in, pool_amount_in, amount_out, amount_received) = match amount {
+        SwapAmount::ExactIn(amount_in) => {
+            let (pool_amount_in, amount_out, amount_received) = price_swap(
+                mint_info,
+                amount_in,
+                sol_reserve,
+                token_reserve,
+                fee_rate,
+                is_sol_to_token,
+            )?;
+            (amount_in, pool_amount_in, amount_out, amount_received)
+        }
+        // With Token-2022 transfer fees, gross up the tokens sent so the exact
+        // amount arrives (at the user on buys, at the pool on sells)
+        SwapAmount::ExactOut(amount_out) if is_sol_to_token => {
+            let amount_sent = math::add(amount_out, inverse_transfer_fee(mint_info, amount_out)?)?;
+            let amount_in = calculate_swap_input(amount_sent, sol_reserve, token_reserve, fee_rate)?;
+            (amount_in, amount_in, amount_sent, amount_out)
+        }
+        SwapAmount::ExactOut(amount_out) => {
+            let pool_amount_in = calculate_swap_input(amount_out, token_reserve, sol_reserve, fee_rate)?;
+            let amount_in = math::add(pool_amount_in, inverse_transfer_fee(mint_info, pool_amount_in)?)?;
+            (amount_in, pool_amount_in, amount_out, amount_out)
+        }
+    };
+
+    let fee_amount = calculate_fee_in_sol(
+        pool_amount_in,
+        amount_out,
+        sol_reserve,
+        token_reserve,
+        fee_rate,
+        is_sol_to_token
+    )?;
+
+    if is_sol_to_token {
+        require!(amount_out <= token_reserve, SwapError::InsufficientLiquidity);
+    } else {
+        require!(math::add(amount_out, fee_amount)? <= sol_reserve, SwapError::InsufficientLiquidity);
+    }
+
+    Ok(PricedSwap {
+        is_sol_to_token,
+        amount_in,
+        pool_amount_in,
+        amount_out,
+        amount_received,
+        fee_amount,
+        trade_volume: sol_volume(amount_in, amount_out, fee_amount, is_sol_to_token)?,
+        price_before: swap_pool.spot_price(),
+        slot,
+    })
+}
+
+// Helper function to take the LP share of a swap fee, which stays in the reserves
+fn lp_fee_share(fee_amount: u64, config: &ProtocolConfig) -> Result<u64> {
+    math::mul_div(fee_amount, config.lp_fee_bps as u64, math::BPS_DENOMINATOR)
+}
+
+// Helper function to emit SwapExecuted with the pool's post-trade reserves
+fn emit_swap_executed(
+    swap_pool: &Account<SwapPool>,
+    trader: Pubkey,
+    is_sol_to_token: bool,
+    amount_in: u64,
+    amount_out: u64,
+    fee_amount: u64,
+    timestamp: i64
+) {
+    emit!(SwapExecuted {
+        swap_pool: swap_pool.key(),
+        token_mint: swap_pool.token_mint,
+        trader,
+        is_sol_to_token,
+        amount_in,
+        amount_out,
+        fee_amount,
+        sol_reserve: swap_pool.sol_reserve,
+        token_reserve: swap_pool.token_reserve,
+        timestamp,
+    });
 }
 
 // Helper function to calculate swap output using constant product formula
@@ -315,149 +1658,2543 @@ fn calculate_swap_output(
     require!(reserve_in > 0 && reserve_out > 0, SwapError::InsufficientLiquidity);
     
     // Apply fee (fee_rate is in basis points, e.g., 30 = 0.3%)
-    let amount_in_with_fee = amount_in * (10000 - fee_rate as u64) / 10000;
+    let amount_in_with_fee = math::mul_div(amount_in, math::sub(math::BPS_DENOMINATOR, fee_rate as u64)?, math::BPS_DENOMINATOR)?;
     
     // Constant product formula: (x + dx) * (y - dy) = x * y
     // Solving for dy: dy = y * dx / (x + dx)
-    let amount_out = (reserve_out as u128 * amount_in_with_fee as u128) 
-        / (reserve_in as u128 + amount_in_with_fee as u128);
-    
-    Ok(amount_out as u64)
+    math::mul_div(reserve_out, amount_in_with_fee, math::add(reserve_in, amount_in_with_fee)?)
 }
 
-// ===== ACCOUNT CONTEXTS =====
+// Helper function to price an exact-input swap. Token-2022 transfer fees are withheld
+// in transit, so sells are priced on what reaches the pool and buys are checked
+// against what reaches the user. Returns (pool_amount_in, amount_out, amount_received).
+fn price_swap(
+    mint_info: &AccountInfo,
+    amount_in: u64,
+    sol_reserve: u64,
+    token_reserve: u64,
+    fee_rate: u16,
+    is_sol_to_token: bool
+) -> Result<(u64, u64, u64)> {
+    if is_sol_to_token {
+        // SOL -> Token swap
+        let amount_out = calculate_swap_output(amount_in, sol_reserve, token_reserve, fee_rate)?;
+        Ok((amount_in, amount_out, math::sub(amount_out, transfer_fee(mint_info, amount_out)?)?))
+    } else {
+        // Token -> SOL swap
+        let pool_amount_in = math::sub(amount_in, transfer_fee(mint_info, amount_in)?)?;
+        let amount_out = calculate_swap_output(pool_amount_in, token_reserve, sol_reserve, fee_rate)?;
+        Ok((pool_amount_in, amount_out, amount_out))
+    }
+}
 
-#[derive(Accounts)]
-pub struct InitSwapPool<'info> {
-    #[account(
-        init,
-        payer = creator,
-        seeds = [b"swap_pool", token_mint.key().as_ref()],
-        bump,
-        space = 8 + 32 + 8 + 8 + 32 + 8 + 2 + 1 + 8 + 1
-    )]
-    pub swap_pool: Account<'info, SwapPool>,
-    
-    pub token_mint: Account<'info, Mint>,
-    
-    #[account(
-        init,
-        payer = creator,
-        mint::decimals = 6,
-        mint::authority = swap_pool,
-        seeds = [b"lp_mint", token_mint.key().as_ref()],
-        bump
-    )]
-    pub lp_mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub creator_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        init,
-        payer = creator,
-        token::mint = token_mint,
-        token::authority = swap_pool,
-        seeds = [b"pool_token", token_mint.key().as_ref()],
-        bump
-    )]
-    pub pool_token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: Pool SOL account (PDA)
-    #[account(
-        mut,
-        seeds = [b"pool_sol", token_mint.key().as_ref()],
-        bump
-    )]
-    pub pool_sol_account: AccountInfo<'info>,
-    
-    #[account(
-        init,
-        payer = creator,
-        token::mint = lp_mint,
-        token::authority = custody_program,
-        seeds = [b"custody_lp", token_mint.key().as_ref()],
-        bump
-    )]
-    pub custody_lp_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: LP custody program
-    pub custody_program: AccountInfo<'info>,
+// Helper function to calculate spot price in SOL per token, scaled by 1e9
+fn calculate_spot_price(sol_reserve: u64, token_reserve: u64) -> u64 {
+    if token_reserve == 0 {
+        return 0;
+    }
+
+    // Price = SOL reserve / Token reserve (scaled by 1e9 for precision)
+    ((sol_reserve as u128 * 1_000_000_000) / token_reserve as u128) as u64
+}
+
+// Helper function to measure a price move in basis points of the starting price
+fn price_impact_bps(price_before: u64, price_after: u64) -> Result<u64> {
+    if price_before == 0 {
+        return Ok(0);
+    }
+
+    math::mul_div(price_before.abs_diff(price_after), math::BPS_DENOMINATOR, price_before)
+}
+
+// Helper function to record a trade's price move against the pool's circuit
+// breaker, emitting CircuitBreakerTripped when it trips
+fn record_price_move(swap_pool: &mut Account<SwapPool>, slot: u64, timestamp: i64) -> Result<()> {
+    if let Some(move_bps) = swap_pool.record_price_move()? {
+        let breaker = &swap_pool.circuit_breaker;
+        emit!(CircuitBreakerTripped {
+            swap_pool: swap_pool.key(),
+            token_mint: swap_pool.token_mint,
+            window_start_price: breaker.window_start_price,
+            price: swap_pool.spot_price(),
+            move_bps,
+            halts_buys: breaker.tripped_up,
+            halts_sells: breaker.tripped_down,
+            slot,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+// Helper function to enforce an optional max price impact
+fn check_price_impact(price_before: u64, price_after: u64, max_price_impact_bps: Option<u16>) -> Result<()> {
+    if let Some(max_bps) = max_price_impact_bps {
+        require!(
+            price_impact_bps(price_before, price_after)? <= max_bps as u64,
+            SwapError::PriceImpactExceeded
+        );
+    }
+
+    Ok(())
+}
+
+// Helper function to reject transactions that land after the caller's deadline
+fn check_deadline(deadline_unix_ts: Option<i64>, now: i64) -> Result<()> {
+    if let Some(deadline) = deadline_unix_ts {
+        require!(now <= deadline, SwapError::DeadlineExceeded);
+    }
+
+    Ok(())
+}
+
+// Helper function to express the swap fee in lamports. Buys pay the fee out of the
+// SOL input; sells pay the SOL value the fee portion of the input would have fetched.
+fn calculate_fee_in_sol(
+    amount_in: u64,
+    amount_out: u64,
+    sol_reserve: u64,
+    token_reserve: u64,
+    fee_rate: u16,
+    is_sol_to_token: bool
+) -> Result<u64> {
+    if is_sol_to_token {
+        let amount_in_with_fee = math::mul_div(amount_in, math::sub(math::BPS_DENOMINATOR, fee_rate as u64)?, math::BPS_DENOMINATOR)?;
+        math::sub(amount_in, amount_in_with_fee)
+    } else {
+        let gross_out = calculate_swap_output(amount_in, token_reserve, sol_reserve, 0)?;
+        Ok(gross_out.saturating_sub(amount_out))
+    }
+}
+
+// Helper function to measure trade volume in SOL on either side of the trade
+fn sol_volume(amount_in: u64, amount_out: u64, fee_sol: u64, is_sol_to_token: bool) -> Result<u64> {
+    if is_sol_to_token { Ok(amount_in) } else { math::add(amount_out, fee_sol) }
+}
+
+// Accounts needed to report a trade to the tracker programs
+struct StatsAccounts<'info> {
+    swap_pool: AccountInfo<'info>,
+    token_mint: AccountInfo<'info>,
+    project_tracker: AccountInfo<'info>,
+    token_volume_tracker: AccountInfo<'info>,
+    daily_tracker: AccountInfo<'info>,
+    project_status_tracker_program: AccountInfo<'info>,
+    category_tracker_program: AccountInfo<'info>,
+}
+
+// Helper function to push real volume and post-trade price into the trackers.
+// The swap pool signs so the trackers can reject forged stats.
+fn record_trade_stats<'info>(
+    accounts: StatsAccounts<'info>,
+    token_mint: Pubkey,
+    bump: u8,
+    trade_volume: u64,
+    price: u64
+) -> Result<()> {
+    let seeds = &[
+        b"swap_pool",
+        token_mint.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+    // Death rule volume and ATH tracking
+    let cpi_accounts = project_status_tracker::cpi::accounts::UpdateStats {
+        tracker: accounts.project_tracker,
+        token_mint: accounts.token_mint,
+        swap_pool: accounts.swap_pool.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(accounts.project_status_tracker_program, cpi_accounts, signer_seeds);
+
+    project_status_tracker::cpi::update_stats(cpi_ctx, trade_volume, price)?;
+
+    // Most Traded Token volume
+    let cpi_accounts = category_tracker::cpi::accounts::RecordTokenTrade {
+        token_volume_tracker: accounts.token_volume_tracker,
+        daily_tracker: accounts.daily_tracker,
+        swap_pool: accounts.swap_pool,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(accounts.category_tracker_program, cpi_accounts, signer_seeds);
+
+    category_tracker::cpi::record_token_trade(cpi_ctx, trade_volume)
+}
+
+// Accounts needed to CPI into fee_rewards::record_trade_fee
+struct FeeAccounts<'info> {
+    rewards_pool: AccountInfo<'info>,
+    daily_game_vault: AccountInfo<'info>,
+    platform_wallet: AccountInfo<'info>,
+    fee_payer: AccountInfo<'info>,
+    token_mint: AccountInfo<'info>,
+    protocol_config: AccountInfo<'info>,
+    fee_authority: AccountInfo<'info>,
+    fee_rewards_program: AccountInfo<'info>,
+    daily_game_vault_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+// Helper function to move a swap fee from a pool SOL account into fee_rewards
+fn record_trade_fee<'info>(
+    accounts: FeeAccounts<'info>,
+    token_mint: Pubkey,
+    pool_sol_bump: u8,
+    fee_sol: u64,
+    trade_volume: u64
+) -> Result<()> {
+    let pool_sol_seeds = &[
+        b"pool_sol",
+        token_mint.as_ref(),
+        &[pool_sol_bump],
+    ];
+
+    let cpi_accounts = fee_rewards::cpi::accounts::RecordFee {
+        rewards_pool: accounts.rewards_pool,
+        daily_game_vault: accounts.daily_game_vault,
+        protocol_config: accounts.protocol_config,
+        platform_wallet: accounts.platform_wallet,
+        fee_payer: accounts.fee_payer,
+        token_mint: accounts.token_mint,
+        fee_authority: accounts.fee_authority,
+        daily_game_vault_program: accounts.daily_game_vault_program,
+        system_program: accounts.system_program,
+    };
+    let signer_seeds: &[&[&[u8]]] = &[pool_sol_seeds];
+    let cpi_ctx = CpiContext::new_with_signer(accounts.fee_rewards_program, cpi_accounts, signer_seeds);
+
+    fee_rewards::cpi::record_trade_fee(cpi_ctx, fee_sol, trade_volume)
+}
+
+// Helper function to calculate the input required for an exact output (inverse of
+// calculate_swap_output). Both divisions round up so the pool never receives less
+// than the constant product requires.
+fn calculate_swap_input(
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_rate: u16
+) -> Result<u64> {
+    require!(reserve_in > 0 && reserve_out > 0, SwapError::InsufficientLiquidity);
+    require!(amount_out < reserve_out, SwapError::InsufficientLiquidity);
+
+    // Solving (x + dx) * (y - dy) = x * y for dx: dx = x * dy / (y - dy)
+    let amount_in_with_fee = math::mul_div_ceil(reserve_in, amount_out, reserve_out - amount_out)?;
+
+    // Gross up for the fee (fee_rate is in basis points)
+    let fee_denominator = math::sub(math::BPS_DENOMINATOR, fee_rate as u64)?;
+    math::mul_div_ceil(amount_in_with_fee, math::BPS_DENOMINATOR, fee_denominator)
+}
+
+// Helper function to read a mint's Token-2022 transfer fee config, if any
+fn transfer_fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+// Helper function to get the fee withheld when `amount` tokens are transferred
+fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| SwapError::MathOverflow.into()),
+        None => Ok(0),
+    }
+}
+
+// Helper function to get the extra tokens to send so that `amount` arrives after the fee
+fn inverse_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| SwapError::MathOverflow.into()),
+        None => Ok(0),
+    }
+}
+
+// Helper function to recognise the lp_custody vault PDA ([b"vault", token_mint])
+fn is_custody_vault(key: &Pubkey, token_mint: &Pubkey) -> bool {
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", token_mint.as_ref()],
+        &lp_custody_program::ID,
+    );
+    *key == vault
+}
+
+// Helper function to apply a pool's anti-sniper limits to a buy spending `sol_amount`.
+// Inside the launch window every buy needs the buyer's guard, which tracks spend
+// per slot and in total so splitting a buy across transactions doesn't help.
+fn enforce_launch_protection(
+    swap_pool: &SwapPool,
+    buyer_guard: Option<&mut Account<BuyerGuard>>,
+    now: i64,
+    sol_amount: u64
+) -> Result<()> {
+    if !swap_pool.launch_protection_active(now) {
+        return Ok(());
+    }
+
+    let guard = buyer_guard.ok_or(SwapError::BuyerGuardRequired)?;
+    let slot = Clock::get()?.slot;
+
+    let (bought_in_slot, total_bought) = launch_buy_totals(guard, slot, sol_amount)?;
+    guard.last_slot = slot;
+    guard.bought_in_slot = bought_in_slot;
+    guard.total_bought = total_bought;
+
+    require!(
+        swap_pool.launch_protection.allows(bought_in_slot, total_bought),
+        SwapError::LaunchBuyLimitExceeded
+    );
+
+    Ok(())
+}
+
+// Helper function to get a buyer's (bought this slot, bought over the window)
+// totals including a new buy of `sol_amount`
+fn launch_buy_totals(guard: &BuyerGuard, slot: u64, sol_amount: u64) -> Result<(u64, u64)> {
+    let bought_in_slot = if guard.last_slot == slot { guard.bought_in_slot } else { 0 };
+    Ok((math::add(bought_in_slot, sol_amount)?, math::add(guard.total_bought, sol_amount)?))
+}
+
+// Helper function to wrap SOL from a wallet into a WSOL token vault
+fn wrap_sol<'info>(
+    from: AccountInfo<'info>,
+    wsol_vault: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    wsol_token_program: AccountInfo<'info>,
+    amount: u64
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            system_program::Transfer {
+                from,
+                to: wsol_vault.clone(),
+            },
+        ),
+        amount,
+    )?;
+
+    token::sync_native(CpiContext::new(
+        wsol_token_program,
+        token::SyncNative { account: wsol_vault },
+    ))
+}
+
+// Helper function to pay lamports out of a native pool's SOL account. The PDA is
+// system-owned, so it has to sign a system transfer rather than be debited directly.
+fn pay_from_pool_sol<'info>(
+    pool_sol_account: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_mint: Pubkey,
+    pool_sol_bump: u8,
+    amount: u64
+) -> Result<()> {
+    let pool_sol_seeds = &[
+        b"pool_sol",
+        token_mint.as_ref(),
+        &[pool_sol_bump],
+    ];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program,
+            system_program::Transfer {
+                from: pool_sol_account,
+                to,
+            },
+            &[pool_sol_seeds],
+        ),
+        amount,
+    )
+}
+
+// How a swap's size is fixed: the exact amount the trader sends, or the exact
+// amount they receive
+#[derive(Clone, Copy)]
+enum SwapAmount {
+    ExactIn(u64),
+    ExactOut(u64),
+}
+
+// A swap that has passed the pool's guards and been priced. `amount_in` and
+// `amount_out` leave the trader and the pool; `pool_amount_in` and
+// `amount_received` are what arrives after any Token-2022 transfer fee.
+struct PricedSwap {
+    is_sol_to_token: bool,
+    amount_in: u64,
+    pool_amount_in: u64,
+    amount_out: u64,
+    amount_received: u64,
+    fee_amount: u64,
+    trade_volume: u64,
+    price_before: u64,
+    slot: u64,
+}
+
+// The pool side of a swap. Every swap instruction builds one per pool it trades
+// against and runs the same pipeline: quote (guards and pricing), then execute
+// (settle, route_fee, record_stats).
+struct SwapCore<'a, 'info> {
+    swap_pool: &'a mut Account<'info, SwapPool>,
+    token_mint: &'a InterfaceAccount<'info, Mint>,
+    pool_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pool_sol_account: AccountInfo<'info>,
+    pool_sol_bump: u8,
+    pool_wsol_vault: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    wsol_token_program: Option<AccountInfo<'info>>,
+    buyer_guard: Option<&'a mut Account<'info, BuyerGuard>>,
+    project_tracker: &'a Account<'info, ProjectTracker>,
+    token_volume_tracker: AccountInfo<'info>,
+    early_buy_tracker: Option<AccountInfo<'info>>,
+    token_program: AccountInfo<'info>,
+}
+
+// Fee routing and stats accounts shared by the pools a swap instruction trades
+struct SwapRouting<'a, 'info> {
+    protocol_config: &'a Account<'info, ProtocolConfig>,
+    rewards_pool: AccountInfo<'info>,
+    daily_game_vault: AccountInfo<'info>,
+    platform_wallet: AccountInfo<'info>,
+    fee_authority: AccountInfo<'info>,
+    fee_rewards_program: AccountInfo<'info>,
+    daily_game_vault_program: AccountInfo<'info>,
+    referrer: Option<&'a mut Account<'info, Referrer>>,
+    daily_tracker: AccountInfo<'info>,
+    profit_tracker: Option<AccountInfo<'info>>,
+    project_status_tracker_program: AccountInfo<'info>,
+    category_tracker_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+// The trader side of a swap. Buys are paid from `sol_source`, a signing wallet or
+// a program-owned escrow; sells pay out to `sol_destination`. `token_account`
+// receives bought tokens or sends sold ones under `token_authority`, which signs
+// with `authority_seeds` when it is a PDA. `trader` is credited in the stats.
+struct SwapTrader<'a, 'info> {
+    trader: AccountInfo<'info>,
+    sol_source: AccountInfo<'info>,
+    sol_source_is_escrow: bool,
+    sol_destination: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    token_authority: AccountInfo<'info>,
+    authority_seeds: Option<&'a [&'a [u8]]>,
+    // Wrapped-SOL sells only
+    wsol_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'a, 'info> SwapTrader<'a, 'info> {
+    // A signing wallet trading from its own accounts
+    fn wallet(wallet: AccountInfo<'info>, token_account: AccountInfo<'info>) -> Self {
+        SwapTrader {
+            trader: wallet.clone(),
+            sol_source: wallet.clone(),
+            sol_source_is_escrow: false,
+            sol_destination: wallet.clone(),
+            token_account,
+            token_authority: wallet,
+            authority_seeds: None,
+            wsol_account: None,
+        }
+    }
+
+    // Pay lamports from the trader's SOL source. Escrow PDAs are owned by this
+    // program, so their lamports move directly; wallets sign a system transfer.
+    fn pay_sol(&self, to: AccountInfo<'info>, system_program: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if self.sol_source_is_escrow {
+            **self.sol_source.try_borrow_mut_lamports()? -= amount;
+            **to.try_borrow_mut_lamports()? += amount;
+            return Ok(());
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: self.sol_source.clone(),
+                    to,
+                },
+            ),
+            amount,
+        )
+    }
+}
+
+impl<'a, 'info> SwapCore<'a, 'info> {
+    // Guard and price a swap. The pool must be open, its books must match its
+    // balances and the circuit breaker must allow the direction; buys also need a
+    // live token and must pass the launch limits. Slippage is left to the caller.
+    fn quote(&mut self, amount: SwapAmount, is_sol_to_token: bool, now: i64) -> Result<PricedSwap> {
+        require!(self.swap_pool.is_active, SwapError::PoolInactive);
+        require!(!self.swap_pool.locked, SwapError::PoolLocked);
+
+        let (token_balance, sol_balance) = pool_balances(
+            self.swap_pool,
+            self.pool_token_account,
+            &self.pool_sol_account,
+            self.pool_wsol_vault,
+        )?;
+        self.swap_pool.check_reserves(token_balance, sol_balance)?;
+        let slot = Clock::get()?.slot;
+        self.swap_pool.check_circuit_breaker(slot, is_sol_to_token)?;
+
+        let swap = price_trade(self.swap_pool, &self.token_mint.to_account_info(), amount, is_sol_to_token, now, slot)?;
+
+        // Buys are disabled for dead tokens; sells stay open until disable_pool
+        if is_sol_to_token {
+            require!(self.project_tracker.status != TokenStatus::Dead, SwapError::BuysDisabledForDeadToken);
+            enforce_launch_protection(self.swap_pool, self.buyer_guard.as_deref_mut(), now, swap.amount_in)?;
+        }
+
+        Ok(swap)
+    }
+
+    // Settle a priced swap, then pay out its fee and report it to the trackers
+    fn execute(
+        &mut self,
+        routing: &mut SwapRouting<'_, 'info>,
+        trader: &SwapTrader<'_, 'info>,
+        swap: &PricedSwap,
+        now: i64,
+        max_price_impact_bps: Option<u16>
+    ) -> Result<()> {
+        let lp_fee = lp_fee_share(swap.fee_amount, routing.protocol_config)?;
+
+        self.settle(trader, swap, lp_fee, routing.system_program.clone(), now)?;
+        check_price_impact(swap.price_before, self.swap_pool.spot_price(), max_price_impact_bps)?;
+        self.route_fee(routing, swap, lp_fee)?;
+        self.record_stats(routing, trader, swap)?;
+
+        emit_swap_executed(
+            self.swap_pool,
+            trader.trader.key(),
+            swap.is_sol_to_token,
+            swap.amount_in,
+            swap.amount_received,
+            swap.fee_amount,
+            now,
+        );
+        Ok(())
+    }
+
+    // Move the funds of a priced swap and update the pool's books. The fee, less the
+    // LP share kept in the reserves, ends up in the pool SOL account for route_fee to
+    // pay out, also for wrapped-SOL pools.
+    fn settle(
+        &mut self,
+        trader: &SwapTrader<'_, 'info>,
+        swap: &PricedSwap,
+        lp_fee: u64,
+        system_program: AccountInfo<'info>,
+        now: i64
+    ) -> Result<()> {
+        let token_mint = self.swap_pool.token_mint;
+        let bump = self.swap_pool.bump;
+        let routed_fee = math::sub(swap.fee_amount, lp_fee)?;
+
+        // Create seeds for signing
+        let seeds = &[
+            b"swap_pool",
+            token_mint.as_ref(),
+            &[bump],
+        ];
+
+        if swap.is_sol_to_token {
+            // Transfer SOL from trader to pool
+            match self.swap_pool.quote_mode {
+                QuoteMode::NativeSol => {
+                    trader.pay_sol(self.pool_sol_account.clone(), system_program, swap.amount_in)?;
+                }
+                QuoteMode::WrappedSol => {
+                    // The routed fee goes to the pool SOL account; the rest is wrapped into the vault
+                    let (pool_wsol_vault, wsol_token_program) = self.wsol_vault()?;
+                    trader.pay_sol(self.pool_sol_account.clone(), system_program.clone(), routed_fee)?;
+                    wrap_sol(
+                        trader.sol_source.clone(),
+                        pool_wsol_vault,
+                        system_program,
+                        wsol_token_program,
+                        math::sub(swap.amount_in, routed_fee)?,
+                    )?;
+                }
+            }
+
+            // Transfer tokens from pool to trader
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.clone(),
+                    TransferChecked {
+                        from: self.pool_token_account.to_account_info(),
+                        mint: self.token_mint.to_account_info(),
+                        to: trader.token_account.clone(),
+                        authority: self.swap_pool.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                swap.amount_out,
+                self.token_mint.decimals,
+            )?;
+        } else {
+            // Transfer tokens from trader to pool
+            let authority_seeds: &[&[&[u8]]] = match &trader.authority_seeds {
+                Some(authority_seeds) => std::slice::from_ref(authority_seeds),
+                None => &[],
+            };
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.clone(),
+                    TransferChecked {
+                        from: trader.token_account.clone(),
+                        mint: self.token_mint.to_account_info(),
+                        to: self.pool_token_account.to_account_info(),
+                        authority: trader.token_authority.clone(),
+                    },
+                    authority_seeds,
+                ),
+                swap.amount_in,
+                self.token_mint.decimals,
+            )?;
+
+            // Transfer SOL from pool to trader
+            match self.swap_pool.quote_mode {
+                QuoteMode::NativeSol => {
+                    pay_from_pool_sol(
+                        self.pool_sol_account.clone(),
+                        trader.sol_destination.clone(),
+                        system_program,
+                        token_mint,
+                        self.pool_sol_bump,
+                        swap.amount_out,
+                    )?;
+                }
+                QuoteMode::WrappedSol => {
+                    // Pay proceeds and routed fee into the trader's WSOL account; the trader
+                    // forwards the fee in SOL to the pool SOL account for route_fee.
+                    // Only an account that held no WSOL before is closed to unwrap,
+                    // so WSOL the trader already had stays wrapped.
+                    let (pool_wsol_vault, wsol_token_program) = self.wsol_vault()?;
+                    let user_wsol = trader.wsol_account.ok_or(SwapError::MissingWsolAccounts)?;
+                    let unwrap = user_wsol.amount == 0;
+                    let user_wsol_account = user_wsol.to_account_info();
+
+                    token::transfer(
+                        CpiContext::new_with_signer(
+                            wsol_token_program.clone(),
+                            token::Transfer {
+                                from: pool_wsol_vault,
+                                to: user_wsol_account.clone(),
+                                authority: self.swap_pool.to_account_info(),
+                            },
+                            &[seeds],
+                        ),
+                        math::add(swap.amount_out, routed_fee)?,
+                    )?;
+                    if unwrap {
+                        token::close_account(CpiContext::new(
+                            wsol_token_program,
+                            token::CloseAccount {
+                                account: user_wsol_account,
+                                destination: trader.sol_destination.clone(),
+                                authority: trader.token_authority.clone(),
+                            },
+                        ))?;
+                    }
+                    trader.pay_sol(self.pool_sol_account.clone(), system_program, routed_fee)?;
+                }
+            }
+        }
+
+        // Update reserves AFTER all token operations
+        let swap_pool = &mut *self.swap_pool;
+        swap_pool.update_price_accumulator(now);
+        (swap_pool.sol_reserve, swap_pool.token_reserve) = swap_pool.reserves_after(swap, lp_fee)?;
+        if swap.is_sol_to_token {
+            swap_pool.total_buys += 1;
+        }
+        swap_pool.accrue_fee(lp_fee);
+        record_price_move(self.swap_pool, swap.slot, now)
+    }
+
+    // Pay the SOL fee, less the LP share, out of the pool into fee_rewards
+    // (holders / games / platform)
+    fn route_fee(&mut self, routing: &mut SwapRouting<'_, 'info>, swap: &PricedSwap, lp_fee: u64) -> Result<()> {
+        let token_mint = self.swap_pool.token_mint;
+
+        // The referrer's share accrues in its PDA until claimed
+        let referral_fee = match routing.referrer.as_deref_mut() {
+            Some(referrer) => {
+                let referral_fee = math::mul_div(
+                    swap.fee_amount,
+                    routing.protocol_config.referral_fee_bps as u64,
+                    math::BPS_DENOMINATOR,
+                )?;
+                if referral_fee > 0 {
+                    pay_from_pool_sol(
+                        self.pool_sol_account.clone(),
+                        referrer.to_account_info(),
+                        routing.system_program.clone(),
+                        token_mint,
+                        self.pool_sol_bump,
+                        referral_fee,
+                    )?;
+                }
+
+                referrer.volume_referred = math::add(referrer.volume_referred, swap.trade_volume)?;
+                referrer.fees_earned = math::add(referrer.fees_earned, referral_fee)?;
+                referrer.fees_unclaimed = math::add(referrer.fees_unclaimed, referral_fee)?;
+                referral_fee
+            }
+            None => 0,
+        };
+
+        record_trade_fee(
+            FeeAccounts {
+                rewards_pool: routing.rewards_pool.clone(),
+                daily_game_vault: routing.daily_game_vault.clone(),
+                platform_wallet: routing.platform_wallet.clone(),
+                fee_payer: self.pool_sol_account.clone(),
+                token_mint: self.token_mint.to_account_info(),
+                protocol_config: routing.protocol_config.to_account_info(),
+                fee_authority: routing.fee_authority.clone(),
+                fee_rewards_program: routing.fee_rewards_program.clone(),
+                daily_game_vault_program: routing.daily_game_vault_program.clone(),
+                system_program: routing.system_program.clone(),
+            },
+            token_mint,
+            self.pool_sol_bump,
+            math::sub(swap.fee_amount, math::add(lp_fee, referral_fee)?)?,
+            swap.trade_volume,
+        )
+    }
+
+    // Report the settled trade to project_status_tracker and category_tracker
+    fn record_stats(&self, routing: &SwapRouting<'_, 'info>, trader: &SwapTrader<'_, 'info>, swap: &PricedSwap) -> Result<()> {
+        let token_mint = self.swap_pool.token_mint;
+        let bump = self.swap_pool.bump;
+
+        record_trade_stats(
+            StatsAccounts {
+                swap_pool: self.swap_pool.to_account_info(),
+                token_mint: self.token_mint.to_account_info(),
+                project_tracker: self.project_tracker.to_account_info(),
+                token_volume_tracker: self.token_volume_tracker.clone(),
+                daily_tracker: routing.daily_tracker.clone(),
+                project_status_tracker_program: routing.project_status_tracker_program.clone(),
+                category_tracker_program: routing.category_tracker_program.clone(),
+            },
+            token_mint,
+            bump,
+            swap.trade_volume,
+            self.swap_pool.spot_price(),
+        )?;
+
+        // Create seeds for signing
+        let seeds = &[
+            b"swap_pool",
+            token_mint.as_ref(),
+            &[bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        // Profit Champion: SOL spent on buys, SOL received on sells
+        if let Some(profit_tracker) = &routing.profit_tracker {
+            let (buy_amount, sell_amount) = if swap.is_sol_to_token { (swap.amount_in, 0) } else { (0, swap.amount_out) };
+            let cpi_accounts = category_tracker::cpi::accounts::RecordProfit {
+                profit_tracker: profit_tracker.clone(),
+                daily_tracker: routing.daily_tracker.clone(),
+                swap_pool: self.swap_pool.to_account_info(),
+                token_mint: self.token_mint.to_account_info(),
+                trader: trader.trader.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(routing.category_tracker_program.clone(), cpi_accounts, signer_seeds);
+
+            category_tracker::cpi::record_profit(cpi_ctx, buy_amount, sell_amount)?;
+        }
+
+        // Early Buyer: the first buys of the pool earn points
+        let total_buys = self.swap_pool.total_buys;
+        if swap.is_sol_to_token && total_buys <= EARLY_BUYER_LIMIT {
+            if let Some(early_buy_tracker) = &self.early_buy_tracker {
+                let cpi_accounts = category_tracker::cpi::accounts::RecordEarlyBuy {
+                    early_buy_tracker: early_buy_tracker.clone(),
+                    daily_tracker: routing.daily_tracker.clone(),
+                    swap_pool: self.swap_pool.to_account_info(),
+                    buyer: trader.trader.clone(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(routing.category_tracker_program.clone(), cpi_accounts, signer_seeds);
+
+                category_tracker::cpi::record_early_buy(cpi_ctx, total_buys as u32)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // WSOL vault and SPL Token program of a wrapped-SOL pool
+    fn wsol_vault(&self) -> Result<(AccountInfo<'info>, AccountInfo<'info>)> {
+        match (self.pool_wsol_vault, &self.wsol_token_program) {
+            (Some(vault), Some(program)) => Ok((vault.to_account_info(), program.clone())),
+            _ => Err(SwapError::MissingWsolAccounts.into()),
+        }
+    }
+}
+
+// ===== ACCOUNT CONTEXTS =====
+
+#[derive(Accounts)]
+pub struct InitSwapPool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"swap_pool", token_mint.key().as_ref()],
+        bump,
+        space = SwapPool::LEN
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+    
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 6,
+        mint::authority = swap_pool,
+        seeds = [b"lp_mint", token_mint.key().as_ref()],
+        bump
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = payer,
+        token::mint = token_mint,
+        token::authority = swap_pool,
+        seeds = [b"pool_token", token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = custody_program,
+        seeds = [b"custody_lp", token_mint.key().as_ref()],
+        bump
+    )]
+    pub custody_lp_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: LP custody program
+    pub custody_program: AccountInfo<'info>,
+    
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitWsolPool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"swap_pool", token_mint.key().as_ref()],
+        bump,
+        space = SwapPool::LEN
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 6,
+        mint::authority = swap_pool,
+        seeds = [b"lp_mint", token_mint.key().as_ref()],
+        bump
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = token_mint,
+        token::authority = swap_pool,
+        seeds = [b"pool_token", token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pool WSOL vault (PDA), created and initialized by the instruction
+    #[account(
+        mut,
+        seeds = [b"pool_wsol", token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_wsol_vault: AccountInfo<'info>,
+
+    #[account(address = native_mint::ID)]
+    pub native_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = custody_program,
+        seeds = [b"custody_lp", token_mint.key().as_ref()],
+        bump
+    )]
+    pub custody_lp_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: LP custody program
+    pub custody_program: AccountInfo<'info>,
+    
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// SPL Token program, which owns the native mint
+    pub wsol_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBuyerGuard<'info> {
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [b"buyer_guard", swap_pool.key().as_ref(), buyer.key().as_ref()],
+        bump,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 1
+    )]
+    pub buyer_guard: Account<'info, BuyerGuard>,
+
+    #[account(seeds = [b"swap_pool", swap_pool.token_mint.as_ref()], bump = swap_pool.bump)]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLpPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"lp_position", swap_pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = 8 + 32 + 32 + 16 + 8 + 8 + 1
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+
+    #[account(seeds = [b"swap_pool", swap_pool.token_mint.as_ref()], bump = swap_pool.bump)]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetLpFees<'info> {
+    pub swap_pool: Account<'info, SwapPool>,
+    #[account(has_one = swap_pool)]
+    pub lp_position: Account<'info, LpPosition>,
+    /// The position owner's LP tokens
+    #[account(token::mint = swap_pool.lp_mint, token::authority = lp_position.owner)]
+    pub lp_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeReferrer<'info> {
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"referrer", owner.key().as_ref()],
+        bump,
+        space = 8 + 32 + 8 + 8 + 8 + 4 + 1
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferral<'info> {
+    #[account(
+        init,
+        payer = trader,
+        seeds = [b"referral", trader.key().as_ref()],
+        bump,
+        space = 8 + 32 + 32 + 8 + 1
+    )]
+    pub referral: Account<'info, ReferralLink>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.owner.as_ref()],
+        bump = referrer.bump,
+        constraint = referrer.owner != trader.key() @ SwapError::SelfReferral
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"referrer", owner.key().as_ref()],
+        bump = referrer.bump,
+        has_one = owner
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSwap<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+    
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+    
+    // Wrapped-SOL pools only
+    #[account(
+        mut,
+        seeds = [b"pool_wsol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_wsol_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// User's WSOL account; sell proceeds are paid into it. An account that was
+    /// empty before the sell is closed to unwrap them.
+    #[account(mut, token::mint = native_mint::ID, token::authority = user)]
+    pub user_wsol_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub wsol_token_program: Option<Program<'info, Token>>,
+    
+    /// Required for buys during the pool's launch protection window
+    #[account(
+        mut,
+        seeds = [b"buyer_guard", swap_pool.key().as_ref(), user.key().as_ref()],
+        bump = buyer_guard.bump
+    )]
+    pub buyer_guard: Option<Account<'info, BuyerGuard>>,
+    
+    #[account(mut)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    // Fee routing accounts
+    /// CHECK: Daily rewards pool, validated by fee_rewards
+    #[account(mut)]
+    pub rewards_pool: AccountInfo<'info>,
+    
+    /// CHECK: Daily game vault, validated by fee_rewards
+    #[account(mut)]
+    pub daily_game_vault: AccountInfo<'info>,
+    
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    /// CHECK: Platform treasury SOL account, validated by fee_rewards
+    #[account(mut)]
+    pub platform_wallet: AccountInfo<'info>,
+    
+    /// CHECK: fee_rewards fee authority PDA, validated by fee_rewards
+    pub fee_authority: AccountInfo<'info>,
+    
+    pub fee_rewards_program: Program<'info, FeeRewards>,
+    
+    /// CHECK: Daily game vault program, validated by fee_rewards
+    pub daily_game_vault_program: AccountInfo<'info>,
+    
+    // Stats accounts
+    #[account(
+        mut,
+        seeds = [b"tracker", swap_pool.token_mint.as_ref()],
+        bump,
+        seeds::program = project_status_tracker::ID
+    )]
+    pub project_tracker: Account<'info, ProjectTracker>,
+    
+    /// CHECK: Token volume tracker, validated by category_tracker
+    #[account(mut)]
+    pub token_volume_tracker: AccountInfo<'info>,
+    
+    /// CHECK: Daily category tracker, validated by category_tracker
+    #[account(mut)]
+    pub daily_tracker: AccountInfo<'info>,
+    
+    /// CHECK: Trader's daily profit tracker, validated by category_tracker
+    #[account(mut)]
+    pub profit_tracker: Option<AccountInfo<'info>>,
+    
+    /// CHECK: Trader's early buy tracker, validated by category_tracker
+    #[account(mut)]
+    pub early_buy_tracker: Option<AccountInfo<'info>>,
+    
+    pub project_status_tracker_program: Program<'info, ProjectStatusTracker>,
+    pub category_tracker_program: Program<'info, CategoryTracker>,
+    
+    /// The trader's referral registration, required when a referrer is named
+    #[account(
+        seeds = [b"referral", user.key().as_ref()],
+        bump = referral.bump
+    )]
+    pub referral: Option<Account<'info, ReferralLink>>,
+    
+    /// Referrer credited with a share of the fee, which must be the one the trader registered with
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.owner.as_ref()],
+        bump = referrer.bump,
+        constraint = referrer.owner != user.key() @ SwapError::SelfReferral,
+        constraint = referral.as_ref().map(|link| link.referrer) == Some(referrer.key()) @ SwapError::ReferrerMismatch
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteSwap<'info> {
+    // Split the accounts into the pool, fee routing and trader sides of a swap
+    fn swap_parts(&mut self, pool_sol_bump: u8) -> (SwapCore<'_, 'info>, SwapRouting<'_, 'info>, SwapTrader<'_, 'info>) {
+        let trader = SwapTrader {
+            wsol_account: self.user_wsol_account.as_ref(),
+            ..SwapTrader::wallet(self.user.to_account_info(), self.user_token_account.to_account_info())
+        };
+
+        (
+            SwapCore {
+                swap_pool: &mut self.swap_pool,
+                token_mint: &self.token_mint,
+                pool_token_account: &self.pool_token_account,
+                pool_sol_account: self.pool_sol_account.to_account_info(),
+                pool_sol_bump,
+                pool_wsol_vault: self.pool_wsol_vault.as_ref(),
+                wsol_token_program: self.wsol_token_program.as_ref().map(|program| program.to_account_info()),
+                buyer_guard: self.buyer_guard.as_mut(),
+                project_tracker: &self.project_tracker,
+                token_volume_tracker: self.token_volume_tracker.to_account_info(),
+                early_buy_tracker: self.early_buy_tracker.clone(),
+                token_program: self.token_program.to_account_info(),
+            },
+            SwapRouting {
+                protocol_config: &self.protocol_config,
+                rewards_pool: self.rewards_pool.to_account_info(),
+                daily_game_vault: self.daily_game_vault.to_account_info(),
+                platform_wallet: self.platform_wallet.to_account_info(),
+                fee_authority: self.fee_authority.to_account_info(),
+                fee_rewards_program: self.fee_rewards_program.to_account_info(),
+                daily_game_vault_program: self.daily_game_vault_program.to_account_info(),
+                referrer: self.referrer.as_mut(),
+                daily_tracker: self.daily_tracker.to_account_info(),
+                profit_tracker: self.profit_tracker.clone(),
+                project_status_tracker_program: self.project_status_tracker_program.to_account_info(),
+                category_tracker_program: self.category_tracker_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+            trader,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct SwapRouted<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap_pool", pool_in.token_mint.as_ref()],
+        bump = pool_in.bump
+    )]
+    pub pool_in: Account<'info, SwapPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_token", pool_in.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_in_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Pool SOL account (PDA) of the pool being sold into
+    #[account(
+        mut,
+        seeds = [b"pool_sol", pool_in.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_in_sol_account: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"swap_pool", pool_out.token_mint.as_ref()],
+        bump = pool_out.bump
+    )]
+    pub pool_out: Account<'info, SwapPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_token", pool_out.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_out_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Pool SOL account (PDA) of the pool being bought from
+    #[account(
+        mut,
+        seeds = [b"pool_sol", pool_out.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_out_sol_account: AccountInfo<'info>,
+    
+    /// Required when the pool being bought from is in its launch protection window
+    #[account(
+        mut,
+        seeds = [b"buyer_guard", pool_out.key().as_ref(), user.key().as_ref()],
+        bump = buyer_guard_out.bump
+    )]
+    pub buyer_guard_out: Option<Account<'info, BuyerGuard>>,
+    
+    #[account(mut, token::mint = pool_in.token_mint)]
+    pub user_token_in_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut, token::mint = pool_out.token_mint)]
+    pub user_token_out_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = pool_in.token_mint)]
+    pub token_mint_in: InterfaceAccount<'info, Mint>,
+    
+    #[account(address = pool_out.token_mint)]
+    pub token_mint_out: InterfaceAccount<'info, Mint>,
+    
+    // Fee routing accounts
+    /// CHECK: Daily rewards pool, validated by fee_rewards
+    #[account(mut)]
+    pub rewards_pool: AccountInfo<'info>,
+    
+    /// CHECK: Daily game vault, validated by fee_rewards
+    #[account(mut)]
+    pub daily_game_vault: AccountInfo<'info>,
+    
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    /// CHECK: Platform treasury SOL account, validated by fee_rewards
+    #[account(mut)]
+    pub platform_wallet: AccountInfo<'info>,
+    
+    /// CHECK: fee_rewards fee authority PDA, validated by fee_rewards
+    pub fee_authority: AccountInfo<'info>,
+    
+    pub fee_rewards_program: Program<'info, FeeRewards>,
+    
+    /// CHECK: Daily game vault program, validated by fee_rewards
+    pub daily_game_vault_program: AccountInfo<'info>,
+    
+    // Stats accounts
+    #[account(
+        mut,
+        seeds = [b"tracker", pool_in.token_mint.as_ref()],
+        bump,
+        seeds::program = project_status_tracker::ID
+    )]
+    pub project_tracker_in: Account<'info, ProjectTracker>,
+    
+    #[account(
+        mut,
+        seeds = [b"tracker", pool_out.token_mint.as_ref()],
+        bump,
+        seeds::program = project_status_tracker::ID
+    )]
+    pub project_tracker_out: Account<'info, ProjectTracker>,
+    
+    /// CHECK: Volume tracker of the first token, validated by category_tracker
+    #[account(mut)]
+    pub token_volume_tracker_in: AccountInfo<'info>,
+    
+    /// CHECK: Volume tracker of the second token, validated by category_tracker
+    #[account(mut)]
+    pub token_volume_tracker_out: AccountInfo<'info>,
+    
+    /// CHECK: Daily category tracker, validated by category_tracker
+    #[account(mut)]
+    pub daily_tracker: AccountInfo<'info>,
+    
+    /// CHECK: Trader's daily profit tracker, validated by category_tracker
+    #[account(mut)]
+    pub profit_tracker: Option<AccountInfo<'info>>,
+    
+    /// CHECK: Trader's early buy tracker for the second token, validated by category_tracker
+    #[account(mut)]
+    pub early_buy_tracker_out: Option<AccountInfo<'info>>,
+    
+    pub project_status_tracker_program: Program<'info, ProjectStatusTracker>,
+    pub category_tracker_program: Program<'info, CategoryTracker>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Token program of the first token
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program of the second token (SPL Token and Token-2022 can be mixed)
+    pub token_program_out: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapRouted<'info> {
+    // Split the accounts into both hops' pool and trader sides plus the shared fee routing
+    fn swap_parts(
+        &mut self,
+        pool_in_sol_bump: u8,
+        pool_out_sol_bump: u8
+    ) -> (SwapCore<'_, 'info>, SwapCore<'_, 'info>, SwapRouting<'_, 'info>, SwapTrader<'_, 'info>, SwapTrader<'_, 'info>) {
+        let user = self.user.to_account_info();
+
+        (
+            SwapCore {
+                swap_pool: &mut self.pool_in,
+                token_mint: &self.token_mint_in,
+                pool_token_account: &self.pool_in_token_account,
+                pool_sol_account: self.pool_in_sol_account.to_account_info(),
+                pool_sol_bump: pool_in_sol_bump,
+                pool_wsol_vault: None,
+                wsol_token_program: None,
+                buyer_guard: None,
+                project_tracker: &self.project_tracker_in,
+                token_volume_tracker: self.token_volume_tracker_in.to_account_info(),
+                early_buy_tracker: None,
+                token_program: self.token_program.to_account_info(),
+            },
+            SwapCore {
+                swap_pool: &mut self.pool_out,
+                token_mint: &self.token_mint_out,
+                pool_token_account: &self.pool_out_token_account,
+                pool_sol_account: self.pool_out_sol_account.to_account_info(),
+                pool_sol_bump: pool_out_sol_bump,
+                pool_wsol_vault: None,
+                wsol_token_program: None,
+                buyer_guard: self.buyer_guard_out.as_mut(),
+                project_tracker: &self.project_tracker_out,
+                token_volume_tracker: self.token_volume_tracker_out.to_account_info(),
+                early_buy_tracker: self.early_buy_tracker_out.clone(),
+                token_program: self.token_program_out.to_account_info(),
+            },
+            SwapRouting {
+                protocol_config: &self.protocol_config,
+                rewards_pool: self.rewards_pool.to_account_info(),
+                daily_game_vault: self.daily_game_vault.to_account_info(),
+                platform_wallet: self.platform_wallet.to_account_info(),
+                fee_authority: self.fee_authority.to_account_info(),
+                fee_rewards_program: self.fee_rewards_program.to_account_info(),
+                daily_game_vault_program: self.daily_game_vault_program.to_account_info(),
+                referrer: None,
+                daily_tracker: self.daily_tracker.to_account_info(),
+                profit_tracker: self.profit_tracker.clone(),
+                project_status_tracker_program: self.project_status_tracker_program.to_account_info(),
+                category_tracker_program: self.category_tracker_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+            SwapTrader::wallet(user.clone(), self.user_token_in_account.to_account_info()),
+            SwapTrader::wallet(user, self.user_token_out_account.to_account_info()),
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct BatchSwap<'info> {
+    // Fee routing accounts
+    /// CHECK: Daily rewards pool, validated by fee_rewards
+    #[account(mut)]
+    pub rewards_pool: AccountInfo<'info>,
+    
+    /// CHECK: Daily game vault, validated by fee_rewards
+    #[account(mut)]
+    pub daily_game_vault: AccountInfo<'info>,
+    
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    /// CHECK: Platform treasury SOL account, validated by fee_rewards
+    #[account(mut)]
+    pub platform_wallet: AccountInfo<'info>,
+    
+    /// CHECK: fee_rewards fee authority PDA, validated by fee_rewards
+    pub fee_authority: AccountInfo<'info>,
+    
+    pub fee_rewards_program: Program<'info, FeeRewards>,
+    
+    /// CHECK: Daily game vault program, validated by fee_rewards
+    pub daily_game_vault_program: AccountInfo<'info>,
+    
+    // Stats accounts
+    /// CHECK: Daily category tracker, validated by category_tracker
+    #[account(mut)]
+    pub daily_tracker: AccountInfo<'info>,
+    
+    /// CHECK: Trader's daily profit tracker, validated by category_tracker
+    #[account(mut)]
+    pub profit_tracker: Option<AccountInfo<'info>>,
+    
+    pub project_status_tracker_program: Program<'info, ProjectStatusTracker>,
+    pub category_tracker_program: Program<'info, CategoryTracker>,
+    
+    /// The trader's referral registration, required when a referrer is named
+    #[account(
+        seeds = [b"referral", user.key().as_ref()],
+        bump = referral.bump
+    )]
+    pub referral: Option<Account<'info, ReferralLink>>,
+    
+    /// Referrer credited with a share of every leg's fee, which must be the one the trader registered with
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.owner.as_ref()],
+        bump = referrer.bump,
+        constraint = referrer.owner != user.key() @ SwapError::SelfReferral,
+        constraint = referral.as_ref().map(|link| link.referrer) == Some(referrer.key()) @ SwapError::ReferrerMismatch
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BatchSwap<'info> {
+    // Load and validate one leg's remaining accounts, then swap it through the
+    // shared pipeline like execute_swap
+    fn swap_leg(
+        &mut self,
+        leg: &BatchLeg,
+        accounts: &[AccountInfo<'info>],
+        now: i64,
+        max_price_impact_bps: Option<u16>
+    ) -> Result<BatchLegResult> {
+        require!(leg.amount_in > 0, SwapError::InvalidAmount);
+
+        let mut swap_pool = Account::<SwapPool>::try_from(&accounts[0])?;
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts[1])?;
+        let pool_token_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+        let pool_sol_account = &accounts[3];
+        let token_program = Interface::<TokenInterface>::try_from(&accounts[5])?;
+        let project_tracker = Account::<ProjectTracker>::try_from(&accounts[6])?;
+        let mut buyer_guard = if accounts[8].key() == crate::ID {
+            None
+        } else {
+            Some(Account::<BuyerGuard>::try_from(&accounts[8])?)
+        };
+        let early_buy_tracker = if accounts[9].key() == crate::ID {
+            None
+        } else {
+            Some(accounts[9].clone())
+        };
+
+        let mint_key = swap_pool.token_mint;
+        require!(swap_pool.quote_mode == QuoteMode::NativeSol, SwapError::UnsupportedQuoteMode);
+
+        // The leg's accounts must all belong to its pool
+        let (pool_token_key, _) = Pubkey::find_program_address(&[b"pool_token", mint_key.as_ref()], &crate::ID);
+        let (pool_sol_key, pool_sol_bump) = Pubkey::find_program_address(&[b"pool_sol", mint_key.as_ref()], &crate::ID);
+        require!(
+            token_mint.key() == mint_key
+                && pool_token_account.key() == pool_token_key
+                && pool_sol_account.key() == pool_sol_key
+                && project_tracker.token_mint == mint_key,
+            SwapError::InvalidBatch
+        );
+        if let Some(guard) = &buyer_guard {
+            require!(
+                guard.swap_pool == swap_pool.key() && guard.buyer == self.user.key(),
+                SwapError::InvalidBatch
+            );
+        }
+
+        let mut core = SwapCore {
+            swap_pool: &mut swap_pool,
+            token_mint: &token_mint,
+            pool_token_account: &pool_token_account,
+            pool_sol_account: pool_sol_account.clone(),
+            pool_sol_bump,
+            pool_wsol_vault: None,
+            wsol_token_program: None,
+            buyer_guard: buyer_guard.as_mut(),
+            project_tracker: &project_tracker,
+            token_volume_tracker: accounts[7].clone(),
+            early_buy_tracker,
+            token_program: token_program.to_account_info(),
+        };
+        let trader = SwapTrader::wallet(self.user.to_account_info(), accounts[4].clone());
+        let mut routing = self.swap_routing();
+
+        let swap = core.quote(SwapAmount::ExactIn(leg.amount_in), leg.is_sol_to_token, now)?;
+        require!(swap.amount_received >= leg.minimum_amount_out, SwapError::SlippageExceeded);
+        core.execute(&mut routing, &trader, &swap, now, max_price_impact_bps)?;
+
+        // Remaining accounts aren't written back automatically
+        swap_pool.exit(&crate::ID)?;
+        if let Some(guard) = &buyer_guard {
+            guard.exit(&crate::ID)?;
+        }
+
+        Ok(BatchLegResult {
+            swap_pool: swap_pool.key(),
+            token_mint: mint_key,
+            is_sol_to_token: leg.is_sol_to_token,
+            amount_in: swap.amount_in,
+            amount_out: swap.amount_received,
+            fee_amount: swap.fee_amount,
+        })
+    }
+
+    // Fee routing and stats accounts shared by every leg
+    fn swap_routing(&mut self) -> SwapRouting<'_, 'info> {
+        SwapRouting {
+            protocol_config: &self.protocol_config,
+            rewards_pool: self.rewards_pool.to_account_info(),
+            daily_game_vault: self.daily_game_vault.to_account_info(),
+            platform_wallet: self.platform_wallet.to_account_info(),
+            fee_authority: self.fee_authority.to_account_info(),
+            fee_rewards_program: self.fee_rewards_program.to_account_info(),
+            daily_game_vault_program: self.daily_game_vault_program.to_account_info(),
+            referrer: self.referrer.as_mut(),
+            daily_tracker: self.daily_tracker.to_account_info(),
+            profit_tracker: self.profit_tracker.clone(),
+            project_status_tracker_program: self.project_status_tracker_program.to_account_info(),
+            category_tracker_program: self.category_tracker_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct GetPrice<'info> {
+    #[account(seeds = [b"swap_pool", swap_pool.token_mint.as_ref()], bump = swap_pool.bump)]
+    pub swap_pool: Account<'info, SwapPool>,
+}
+
+#[derive(Accounts)]
+pub struct FlashSwap<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+
+    /// Receives borrowed tokens
+    #[account(mut, token::mint = token_mint)]
+    pub borrower_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Program invoked with the remaining accounts once the loan is sent
+    #[account(executable)]
+    pub callback_program: AccountInfo<'info>,
+
+    // Fee routing accounts
+    /// CHECK: Daily rewards pool, validated by fee_rewards
+    #[account(mut)]
+    pub rewards_pool: AccountInfo<'info>,
+
+    /// CHECK: Daily game vault, validated by fee_rewards
+    #[account(mut)]
+    pub daily_game_vault: AccountInfo<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: Platform treasury SOL account, validated by fee_rewards
+    #[account(mut)]
+    pub platform_wallet: AccountInfo<'info>,
+
+    /// CHECK: fee_rewards fee authority PDA, validated by fee_rewards
+    pub fee_authority: AccountInfo<'info>,
+
+    pub fee_rewards_program: Program<'info, FeeRewards>,
+
+    /// CHECK: Daily game vault program, validated by fee_rewards
+    pub daily_game_vault_program: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: OrderParams)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"limit_order", owner.key().as_ref(), &params.order_id.to_le_bytes()],
+        bump,
+        space = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(seeds = [b"swap_pool", swap_pool.token_mint.as_ref()], bump = swap_pool.bump)]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = token_mint,
+        token::authority = order,
+        seeds = [b"order_escrow", order.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint, token::authority = owner)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"limit_order", order.owner.as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        has_one = swap_pool,
+        has_one = owner,
+        close = owner
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    /// CHECK: Order owner, receives proceeds and the order's rent
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"order_escrow", order.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint, token::authority = owner)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner's buyer guard, required for buy orders during the launch protection window
+    #[account(
+        mut,
+        seeds = [b"buyer_guard", swap_pool.key().as_ref(), owner.key().as_ref()],
+        bump = buyer_guard.bump
+    )]
+    pub buyer_guard: Option<Account<'info, BuyerGuard>>,
+
+    // Fee routing accounts
+    /// CHECK: Daily rewards pool, validated by fee_rewards
+    #[account(mut)]
+    pub rewards_pool: AccountInfo<'info>,
+
+    /// CHECK: Daily game vault, validated by fee_rewards
+    #[account(mut)]
+    pub daily_game_vault: AccountInfo<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: Platform treasury SOL account, validated by fee_rewards
+    #[account(mut)]
+    pub platform_wallet: AccountInfo<'info>,
+
+    /// CHECK: fee_rewards fee authority PDA, validated by fee_rewards
+    pub fee_authority: AccountInfo<'info>,
+
+    pub fee_rewards_program: Program<'info, FeeRewards>,
+
+    /// CHECK: Daily game vault program, validated by fee_rewards
+    pub daily_game_vault_program: AccountInfo<'info>,
+
+    // Stats accounts
+    #[account(
+        mut,
+        seeds = [b"tracker", swap_pool.token_mint.as_ref()],
+        bump,
+        seeds::program = project_status_tracker::ID
+    )]
+    pub project_tracker: Account<'info, ProjectTracker>,
+
+    /// CHECK: Token volume tracker, validated by category_tracker
+    #[account(mut)]
+    pub token_volume_tracker: AccountInfo<'info>,
+
+    /// CHECK: Daily category tracker, validated by category_tracker
+    #[account(mut)]
+    pub daily_tracker: AccountInfo<'info>,
+
+    /// CHECK: Owner's daily profit tracker, validated by category_tracker
+    #[account(mut)]
+    pub profit_tracker: Option<AccountInfo<'info>>,
+
+    /// CHECK: Owner's early buy tracker, validated by category_tracker
+    #[account(mut)]
+    pub early_buy_tracker: Option<AccountInfo<'info>>,
+
+    pub project_status_tracker_program: Program<'info, ProjectStatusTracker>,
+    pub category_tracker_program: Program<'info, CategoryTracker>,
+
+    #[account(mut)]
+    pub filler: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FillOrder<'info> {
+    // Split the accounts into the pool, fee routing and trader sides of the fill.
+    // Buys are paid from the order's escrowed SOL and deliver to the owner; sells
+    // draw the escrowed tokens and pay the owner.
+    fn swap_parts(
+        &mut self,
+        pool_sol_bump: u8,
+        is_sol_to_token: bool
+    ) -> (SwapCore<'_, 'info>, SwapRouting<'_, 'info>, SwapTrader<'_, 'info>) {
+        let order = self.order.to_account_info();
+        let token_account = if is_sol_to_token {
+            self.owner_token_account.to_account_info()
+        } else {
+            self.escrow_token_account.to_account_info()
+        };
+
+        (
+            SwapCore {
+                swap_pool: &mut self.swap_pool,
+                token_mint: &self.token_mint,
+                pool_token_account: &self.pool_token_account,
+                pool_sol_account: self.pool_sol_account.to_account_info(),
+                pool_sol_bump,
+                pool_wsol_vault: None,
+                wsol_token_program: None,
+                buyer_guard: self.buyer_guard.as_mut(),
+                project_tracker: &self.project_tracker,
+                token_volume_tracker: self.token_volume_tracker.to_account_info(),
+                early_buy_tracker: self.early_buy_tracker.clone(),
+                token_program: self.token_program.to_account_info(),
+            },
+            SwapRouting {
+                protocol_config: &self.protocol_config,
+                rewards_pool: self.rewards_pool.to_account_info(),
+                daily_game_vault: self.daily_game_vault.to_account_info(),
+                platform_wallet: self.platform_wallet.to_account_info(),
+                fee_authority: self.fee_authority.to_account_info(),
+                fee_rewards_program: self.fee_rewards_program.to_account_info(),
+                daily_game_vault_program: self.daily_game_vault_program.to_account_info(),
+                referrer: None,
+                daily_tracker: self.daily_tracker.to_account_info(),
+                profit_tracker: self.profit_tracker.clone(),
+                project_status_tracker_program: self.project_status_tracker_program.to_account_info(),
+                category_tracker_program: self.category_tracker_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+            SwapTrader {
+                trader: self.owner.to_account_info(),
+                sol_source: order.clone(),
+                sol_source_is_escrow: true,
+                sol_destination: self.owner.to_account_info(),
+                token_account,
+                token_authority: order,
+                authority_seeds: None,
+                wsol_account: None,
+            },
+        )
+    }
+
+    // Close the (now empty) escrow token account, returning its rent to the owner
+    fn close_escrow(&self, owner: Pubkey, order_id: u64, order_bump: u8) -> Result<()> {
+        let order_seeds = &[
+            b"limit_order",
+            owner.as_ref(),
+            &order_id.to_le_bytes(),
+            &[order_bump],
+        ];
+
+        token_interface::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            token_interface::CloseAccount {
+                account: self.escrow_token_account.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.order.to_account_info(),
+            },
+            &[order_seeds],
+        ))
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"limit_order", owner.key().as_ref(), &order.order_id.to_le_bytes()],
+        bump = order.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(address = escrow_token_account.mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"order_escrow", order.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint, token::authority = owner)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: DcaParams)]
+pub struct OpenDcaVault<'info> {
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"dca_vault", owner.key().as_ref(), &params.vault_id.to_le_bytes()],
+        bump,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 4 + 4 + 8 + 8 + 8 + 8 + 8 + 8 + 1
+    )]
+    pub dca_vault: Account<'info, DcaVault>,
+
+    #[account(seeds = [b"swap_pool", swap_pool.token_mint.as_ref()], bump = swap_pool.bump)]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = token_mint,
+        token::authority = dca_vault,
+        seeds = [b"dca_tokens", dca_vault.key().as_ref()],
+        bump
+    )]
+    pub dca_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDcaSlice<'info> {
+    #[account(
+        mut,
+        seeds = [b"dca_vault", dca_vault.owner.as_ref(), &dca_vault.vault_id.to_le_bytes()],
+        bump = dca_vault.bump,
+        has_one = swap_pool
+    )]
+    pub dca_vault: Account<'info, DcaVault>,
+
+    /// CHECK: Vault owner, credited with the slices in the trader stats
+    #[account(address = dca_vault.owner)]
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dca_tokens", dca_vault.key().as_ref()],
+        bump
+    )]
+    pub dca_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner's buyer guard, required during the launch protection window
+    #[account(
+        mut,
+        seeds = [b"buyer_guard", swap_pool.key().as_ref(), dca_vault.owner.as_ref()],
+        bump = buyer_guard.bump
+    )]
+    pub buyer_guard: Option<Account<'info, BuyerGuard>>,
+
+    // Fee routing accounts
+    /// CHECK: Daily rewards pool, validated by fee_rewards
+    #[account(mut)]
+    pub rewards_pool: AccountInfo<'info>,
+
+    /// CHECK: Daily game vault, validated by fee_rewards
+    #[account(mut)]
+    pub daily_game_vault: AccountInfo<'info>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: Platform treasury SOL account, validated by fee_rewards
+    #[account(mut)]
+    pub platform_wallet: AccountInfo<'info>,
+
+    /// CHECK: fee_rewards fee authority PDA, validated by fee_rewards
+    pub fee_authority: AccountInfo<'info>,
+
+    pub fee_rewards_program: Program<'info, FeeRewards>,
+
+    /// CHECK: Daily game vault program, validated by fee_rewards
+    pub daily_game_vault_program: AccountInfo<'info>,
+
+    // Stats accounts
+    #[account(
+        mut,
+        seeds = [b"tracker", swap_pool.token_mint.as_ref()],
+        bump,
+        seeds::program = project_status_tracker::ID
+    )]
+    pub project_tracker: Account<'info, ProjectTracker>,
+
+    /// CHECK: Token volume tracker, validated by category_tracker
+    #[account(mut)]
+    pub token_volume_tracker: AccountInfo<'info>,
+
+    /// CHECK: Daily category tracker, validated by category_tracker
+    #[account(mut)]
+    pub daily_tracker: AccountInfo<'info>,
+
+    /// CHECK: Owner's daily profit tracker, validated by category_tracker
+    #[account(mut)]
+    pub profit_tracker: Option<AccountInfo<'info>>,
+
+    /// CHECK: Owner's early buy tracker, validated by category_tracker
+    #[account(mut)]
+    pub early_buy_tracker: Option<AccountInfo<'info>>,
+
+    pub project_status_tracker_program: Program<'info, ProjectStatusTracker>,
+    pub category_tracker_program: Program<'info, CategoryTracker>,
+
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteDcaSlice<'info> {
+    // Split the accounts into the pool, fee routing and trader sides of a slice.
+    // The vault pays for the buy and its token account receives the tokens.
+    fn swap_parts(&mut self, pool_sol_bump: u8) -> (SwapCore<'_, 'info>, SwapRouting<'_, 'info>, SwapTrader<'_, 'info>) {
+        let dca_vault = self.dca_vault.to_account_info();
+
+        (
+            SwapCore {
+                swap_pool: &mut self.swap_pool,
+                token_mint: &self.token_mint,
+                pool_token_account: &self.pool_token_account,
+                pool_sol_account: self.pool_sol_account.to_account_info(),
+                pool_sol_bump,
+                pool_wsol_vault: None,
+                wsol_token_program: None,
+                buyer_guard: self.buyer_guard.as_mut(),
+                project_tracker: &self.project_tracker,
+                token_volume_tracker: self.token_volume_tracker.to_account_info(),
+                early_buy_tracker: self.early_buy_tracker.clone(),
+                token_program: self.token_program.to_account_info(),
+            },
+            SwapRouting {
+                protocol_config: &self.protocol_config,
+                rewards_pool: self.rewards_pool.to_account_info(),
+                daily_game_vault: self.daily_game_vault.to_account_info(),
+                platform_wallet: self.platform_wallet.to_account_info(),
+                fee_authority: self.fee_authority.to_account_info(),
+                fee_rewards_program: self.fee_rewards_program.to_account_info(),
+                daily_game_vault_program: self.daily_game_vault_program.to_account_info(),
+                referrer: None,
+                daily_tracker: self.daily_tracker.to_account_info(),
+                profit_tracker: self.profit_tracker.clone(),
+                project_status_tracker_program: self.project_status_tracker_program.to_account_info(),
+                category_tracker_program: self.category_tracker_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+            SwapTrader {
+                trader: self.owner.to_account_info(),
+                sol_source: dca_vault.clone(),
+                sol_source_is_escrow: true,
+                sol_destination: self.owner.to_account_info(),
+                token_account: self.dca_token_account.to_account_info(),
+                token_authority: dca_vault,
+                authority_seeds: None,
+                wsol_account: None,
+            },
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawDcaTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"dca_vault", owner.key().as_ref(), &dca_vault.vault_id.to_le_bytes()],
+        bump = dca_vault.bump,
+        has_one = owner
+    )]
+    pub dca_vault: Account<'info, DcaVault>,
+
+    #[account(address = dca_token_account.mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"dca_tokens", dca_vault.key().as_ref()],
+        bump
+    )]
+    pub dca_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint, token::authority = owner)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawDcaTokens<'info> {
+    // Move bought tokens from the vault's token account to the owner
+    fn transfer_tokens_to_owner(&self, amount: u64) -> Result<()> {
+        let owner = self.owner.key();
+        let vault_id = self.dca_vault.vault_id.to_le_bytes();
+        let dca_seeds = &[
+            b"dca_vault",
+            owner.as_ref(),
+            &vault_id,
+            &[self.dca_vault.bump],
+        ];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.dca_token_account.to_account_info(),
+                    mint: self.token_mint.to_account_info(),
+                    to: self.owner_token_account.to_account_info(),
+                    authority: self.dca_vault.to_account_info(),
+                },
+                &[dca_seeds],
+            ),
+            amount,
+            self.token_mint.decimals,
+        )
+    }
+
+    // Close the (now empty) vault token account, returning its rent to the owner
+    fn close_token_account(&self) -> Result<()> {
+        let owner = self.owner.key();
+        let vault_id = self.dca_vault.vault_id.to_le_bytes();
+        let dca_seeds = &[
+            b"dca_vault",
+            owner.as_ref(),
+            &vault_id,
+            &[self.dca_vault.bump],
+        ];
+
+        token_interface::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            token_interface::CloseAccount {
+                account: self.dca_token_account.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.dca_vault.to_account_info(),
+            },
+            &[dca_seeds],
+        ))
+    }
+}
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(seeds = [b"swap_pool", swap_pool.token_mint.as_ref()], bump = swap_pool.bump)]
+    pub swap_pool: Account<'info, SwapPool>,
+
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(seeds = [b"pool_token", swap_pool.token_mint.as_ref()], bump)]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pool SOL account (PDA)
+    #[account(seeds = [b"pool_sol", swap_pool.token_mint.as_ref()], bump)]
+    pub pool_sol_account: AccountInfo<'info>,
+
+    // Wrapped-SOL pools only
+    #[account(seeds = [b"pool_wsol", swap_pool.token_mint.as_ref()], bump)]
+    pub pool_wsol_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The buyer's guard, checked against the launch window limits
+    #[account(has_one = swap_pool)]
+    pub buyer_guard: Option<Account<'info, BuyerGuard>>,
+
+    #[account(
+        seeds = [b"tracker", swap_pool.token_mint.as_ref()],
+        bump,
+        seeds::program = project_status_tracker::ID
+    )]
+    pub project_tracker: Account<'info, ProjectTracker>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+    
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(address = swap_pool.lp_mint)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+    
+    // Wrapped-SOL pools only
+    #[account(
+        mut,
+        seeds = [b"pool_wsol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_wsol_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub wsol_token_program: Option<Program<'info, Token>>,
+    
+    #[account(mut)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+    )]
+    pub user_lp_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Provider's fee-tracking position on this pool
+    #[account(
+        mut,
+        seeds = [b"lp_position", swap_pool.key().as_ref(), user.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Option<Account<'info, LpPosition>>,
+    
+    // LP MVP tracking; skipped unless all three are passed
+    /// CHECK: Provider's daily LP tracker, validated by category_tracker
+    #[account(mut)]
+    pub lp_tracker: Option<AccountInfo<'info>>,
+    
+    /// CHECK: Daily category tracker, validated by category_tracker
+    #[account(mut)]
+    pub daily_tracker: Option<AccountInfo<'info>>,
+    
+    pub category_tracker_program: Option<Program<'info, CategoryTracker>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddLiquidity<'info> {
+    // Feed the LP MVP category with the SOL-equivalent value of the deposit
+    fn record_lp_provision(&self, token_mint: Pubkey, bump: u8, sol_value: u64) -> Result<()> {
+        let (lp_tracker, daily_tracker, category_tracker_program) = match (
+            &self.lp_tracker,
+            &self.daily_tracker,
+            &self.category_tracker_program,
+        ) {
+            (Some(lp_tracker), Some(daily_tracker), Some(program)) => (lp_tracker, daily_tracker, program),
+            _ => return Ok(()),
+        };
+
+        // Create seeds for signing
+        let seeds = &[
+            b"swap_pool",
+            token_mint.as_ref(),
+            &[bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        let cpi_accounts = category_tracker::cpi::accounts::RecordLPProvision {
+            lp_tracker: lp_tracker.to_account_info(),
+            daily_tracker: daily_tracker.to_account_info(),
+            swap_pool: self.swap_pool.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            provider: self.user.to_account_info(),
+        };
+        let cpi_program = category_tracker_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        category_tracker::cpi::record_lp_provision(cpi_ctx, sol_value)
+    }
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+    
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut, address = swap_pool.lp_mint)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+    
+    // Wrapped-SOL pools only
+    #[account(
+        mut,
+        seeds = [b"pool_wsol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_wsol_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Receives the SOL share as WSOL (wrapped-SOL pools only)
+    #[account(mut, token::mint = native_mint::ID, token::authority = user)]
+    pub user_wsol_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub wsol_token_program: Option<Program<'info, Token>>,
+    
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+    )]
+    pub user_lp_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut, token::mint = swap_pool.token_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Provider's fee-tracking position on this pool
+    #[account(
+        mut,
+        seeds = [b"lp_position", swap_pool.key().as_ref(), user.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Option<Account<'info, LpPosition>>,
     
     #[account(mut)]
-    pub creator: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ExecuteSwap<'info> {
+pub struct DisablePool<'info> {
     #[account(
         mut,
         seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
         bump = swap_pool.bump
     )]
     pub swap_pool: Account<'info, SwapPool>,
-    
-    #[account(mut)]
-    pub pool_token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: Pool SOL account
-    #[account(mut)]
-    pub pool_sol_account: AccountInfo<'info>,
-    
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    /// Protocol admin, or the token's lp_custody vault when distributing a dead token
+    #[account(
+        constraint = protocol_config.is_admin(&authority.key())
+            || is_custody_vault(&authority.key(), &swap_pool.token_mint)
+            @ SwapError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigurePool<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
+    pub swap_pool: Account<'info, SwapPool>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(constraint = protocol_config.is_admin(&authority.key()) @ SwapError::Unauthorized)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// CHECK: Pool in an older layout, checked and deserialized by the handler
     #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    
+    pub swap_pool: UncheckedAccount<'info>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
     #[account(mut)]
-    pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct GetPrice<'info> {
-    #[account(seeds = [b"swap_pool", swap_pool.token_mint.as_ref()], bump = swap_pool.bump)]
+pub struct RefreshFeeSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
+        bump = swap_pool.bump
+    )]
     pub swap_pool: Account<'info, SwapPool>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
 #[derive(Accounts)]
-pub struct AddLiquidity<'info> {
+pub struct SyncReserves<'info> {
     #[account(
         mut,
         seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
         bump = swap_pool.bump
     )]
     pub swap_pool: Account<'info, SwapPool>,
-    
-    pub lp_mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub pool_token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: Pool SOL account
-    #[account(mut)]
+
+    #[account(
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
     pub pool_sol_account: AccountInfo<'info>,
-    
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub user_lp_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+
+    // Wrapped-SOL pools only
+    #[account(
+        seeds = [b"pool_wsol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_wsol_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
-pub struct DisablePool<'info> {
+pub struct SkimReserves<'info> {
     #[account(
-        mut,
         seeds = [b"swap_pool", swap_pool.token_mint.as_ref()],
         bump = swap_pool.bump
     )]
     pub swap_pool: Account<'info, SwapPool>,
-    pub authority: Signer<'info>, // Should be project status tracker
+
+    #[account(address = swap_pool.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_token", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Pool SOL account (PDA)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_sol_account: AccountInfo<'info>,
+
+    // Wrapped-SOL pools only
+    #[account(
+        mut,
+        seeds = [b"pool_wsol", swap_pool.token_mint.as_ref()],
+        bump
+    )]
+    pub pool_wsol_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: Platform treasury SOL account from the protocol config
+    #[account(mut, address = protocol_config.platform_treasury)]
+    pub platform_treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = protocol_config.platform_treasury,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Wrapped-SOL pools only
+    #[account(
+        mut,
+        token::mint = native_mint::ID,
+        token::authority = protocol_config.platform_treasury,
+    )]
+    pub treasury_wsol_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub wsol_token_program: Option<Program<'info, Token>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 // ===== DATA STRUCTURES =====
@@ -473,6 +4210,586 @@ pub struct SwapPool {
     pub is_active: bool,           // Pool active status
     pub created_at: i64,           // Pool creation timestamp
     pub bump: u8,                  // PDA bump
+    pub total_buys: u64,           // Number of SOL -> Token swaps executed
+    pub price_cumulative: u128,    // Sum of spot price * seconds (wrapping)
+    pub last_price_update: i64,    // Last time price_cumulative was updated
+    pub observation_index: u8,     // Slot of the most recent observation
+    pub observations: [PriceObservation; TWAP_OBSERVATIONS], // Ring buffer for TWAP
+    pub quote_mode: QuoteMode,     // Where the pool's SOL side is held
+    pub launch_protection: LaunchProtection, // Anti-sniper settings for the launch window
+    pub locked: bool,              // Set while a flash swap callback is running
+    pub fee_growth_per_share: u128, // Trading fees per LP token, Q64.64 lamports (wrapping)
+    pub circuit_breaker: CircuitBreaker, // Halts trading after sharp price moves
+    pub fee_schedule: FeeSchedule, // Governance bounds and premiums of the dynamic fee
+}
+
+/// Per-pool price circuit breaker. When the spot price moves more than
+/// max_move_bps from where it stood at the start of a window, trades that would
+/// push it further the same way are rejected until the window ends.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct CircuitBreaker {
+    pub max_move_bps: u16,         // Largest price move allowed per window (0 = off)
+    pub window_slots: u64,         // Window length in slots (0 = a single slot)
+    pub window_start_slot: u64,    // Slot the current window started in
+    pub window_start_price: u64,   // Spot price when the window started
+    pub tripped_up: bool,          // Buys halted until the window ends
+    pub tripped_down: bool,        // Sells halted until the window ends
+}
+
+/// Anti-sniper limits for the first seconds of a pool, copied from the protocol
+/// config at creation. Amounts are SOL spent on buys; zero disables the limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LaunchProtection {
+    pub duration: i64,             // Seconds after pool creation the window lasts (0 = off)
+    pub max_buy_per_slot: u64,     // Max SOL a wallet can spend on buys in one slot
+    pub max_buy_per_wallet: u64,   // Max SOL a wallet can spend on buys over the window
+    pub launch_fee_rate: u16,      // Fee at pool creation in bps, decays linearly to fee_rate
+}
+
+impl LaunchProtection {
+    /// Snapshot of the protocol-wide launch settings, taken when a pool is created
+    pub fn from_config(config: &ProtocolConfig) -> Self {
+        LaunchProtection {
+            duration: config.launch_window,
+            max_buy_per_slot: config.launch_max_buy_per_slot,
+            max_buy_per_wallet: config.launch_max_buy_per_wallet,
+            launch_fee_rate: config.launch_fee_bps,
+        }
+    }
+
+    /// Whether a wallet's buy totals stay within the per-slot and per-wallet limits
+    pub fn allows(&self, bought_in_slot: u64, total_bought: u64) -> bool {
+        (self.max_buy_per_slot == 0 || bought_in_slot <= self.max_buy_per_slot)
+            && (self.max_buy_per_wallet == 0 || total_bought <= self.max_buy_per_wallet)
+    }
+}
+
+/// Dynamic fee settings. The fee is fee_rate plus a premium for young pools and
+/// one for realized volatility, kept within [min_fee_rate, max_fee_rate].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct FeeSchedule {
+    pub min_fee_rate: u16,         // Lowest fee in bps
+    pub max_fee_rate: u16,         // Highest fee in bps
+    pub young_pool_age: i64,       // Seconds over which the young pool premium decays (0 = off)
+    pub young_pool_fee_rate: u16,  // Premium at pool creation in bps
+    pub volatility_fee_share: u16, // Share of realized volatility added, in bps
+}
+
+impl FeeSchedule {
+    /// Snapshot of the protocol-wide fee settings, taken when a pool is created
+    /// and on refresh_fee_schedule
+    pub fn from_config(config: &ProtocolConfig) -> Self {
+        FeeSchedule {
+            min_fee_rate: config.min_fee_bps,
+            max_fee_rate: config.max_fee_bps,
+            young_pool_age: config.young_pool_age,
+            young_pool_fee_rate: config.young_pool_fee_bps,
+            volatility_fee_share: config.volatility_fee_bps,
+        }
+    }
+}
+
+#[account]
+pub struct BuyerGuard {
+    pub swap_pool: Pubkey,         // Pool being protected
+    pub buyer: Pubkey,             // Wallet being tracked
+    pub last_slot: u64,            // Slot of the most recent buy
+    pub bought_in_slot: u64,       // SOL spent on buys in last_slot
+    pub total_bought: u64,         // SOL spent on buys during the window
+    pub bump: u8,                  // PDA bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteMode {
+    NativeSol,                     // Lamports in the pool_sol PDA
+    WrappedSol,                    // WSOL in the pool_wsol token vault owned by the pool PDA
+}
+
+#[account]
+pub struct Referrer {
+    pub owner: Pubkey,             // Wallet that receives the referral fees
+    pub volume_referred: u64,      // SOL volume of swaps naming this referrer
+    pub fees_earned: u64,          // Lifetime referral fees (lamports)
+    pub fees_unclaimed: u64,       // Fees held in this account awaiting claim
+    pub unique_traders: u32,       // Traders registered with this referrer
+    pub bump: u8,                  // PDA bump
+}
+
+#[account]
+pub struct ReferralLink {
+    pub trader: Pubkey,            // Referred trader
+    pub referrer: Pubkey,          // Referrer account the trader registered with
+    pub registered_at: i64,        // When the referral was registered
+    pub bump: u8,                  // PDA bump
+}
+
+#[account]
+pub struct LpPosition {
+    pub owner: Pubkey,             // Liquidity provider
+    pub swap_pool: Pubkey,         // Pool the position is in
+    pub fee_growth_checkpoint: u128, // Pool fee_growth_per_share when fees were last settled
+    pub fees_accrued: u64,         // Fees earned up to the checkpoint (lamports)
+    pub sol_value_added: u64,      // SOL-equivalent value of all deposits
+    pub bump: u8,                  // PDA bump
+}
+
+impl LpPosition {
+    /// Fees earned up to `fee_growth` with `lp_balance` LP tokens held since the
+    /// last settlement, including those not yet settled
+    pub fn fees_earned(&self, lp_balance: u64, fee_growth: u128) -> Result<u64> {
+        let pending = math::fees_for_shares(lp_balance, fee_growth.wrapping_sub(self.fee_growth_checkpoint))?;
+        math::add(self.fees_accrued, pending)
+    }
+
+    /// Move earned fees into fees_accrued; called with the LP balance before it changes
+    pub fn settle(&mut self, lp_balance: u64, fee_growth: u128) -> Result<()> {
+        self.fees_accrued = self.fees_earned(lp_balance, fee_growth)?;
+        self.fee_growth_checkpoint = fee_growth;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct LimitOrder {
+    pub owner: Pubkey,             // Trader who placed the order
+    pub swap_pool: Pubkey,         // Pool the order executes against
+    pub order_id: u64,             // Owner-chosen id (PDA seed)
+    pub is_sol_to_token: bool,     // Buy (SOL -> token) or sell (token -> SOL)
+    pub amount_in: u64,            // Escrowed SOL (buys) or tokens (sells)
+    pub min_amount_out: u64,       // Minimum received when filled
+    pub target_price: u64,         // Trigger price (1e9 scaled); sells fill at or above, buys at or below
+    pub twap_window: i64,          // Trigger on the TWAP over this many seconds (0 = spot price)
+    pub expiry: i64,               // Order can't be filled after this time
+    pub filler_bounty: u64,        // Lamports paid to whoever fills the order
+    pub created_at: i64,           // When the order was placed
+    pub bump: u8,                  // PDA bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OrderParams {
+    pub order_id: u64,
+    pub is_sol_to_token: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub target_price: u64,
+    pub twap_window: i64,
+    pub expiry: i64,
+    pub filler_bounty: u64,
+}
+
+#[account]
+pub struct DcaVault {
+    pub owner: Pubkey,             // Trader accumulating the token
+    pub swap_pool: Pubkey,         // Pool the slices buy from
+    pub vault_id: u64,             // Owner-chosen id (PDA seed)
+    pub amount_per_interval: u64,  // Lamports spent per slice
+    pub interval: i64,             // Seconds between slices
+    pub intervals_total: u32,      // Slices funded at opening
+    pub intervals_done: u32,       // Slices executed so far
+    pub max_price: u64,            // Highest average fill price (1e9 scaled) a slice accepts
+    pub crank_bounty: u64,         // Lamports paid to whoever executes a slice
+    pub next_execution_at: i64,    // Earliest time the next slice can execute
+    pub sol_spent: u64,            // Lamports spent on executed slices
+    pub tokens_bought: u64,        // Tokens received by executed slices
+    pub created_at: i64,           // When the vault was opened
+    pub bump: u8,                  // PDA bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DcaParams {
+    pub vault_id: u64,
+    pub amount_per_interval: u64,
+    pub interval: i64,
+    pub intervals: u32,
+    pub max_price: u64,
+    pub crank_bounty: u64,
+}
+
+/// One exact-input leg of a batch_swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchLeg {
+    pub amount_in: u64,            // SOL in for buys, tokens in for sells
+    pub minimum_amount_out: u64,   // Slippage limit for this leg
+    pub is_sol_to_token: bool,     // Buy (true) or sell (false)
+}
+
+/// Result of one batch_swap leg, returned in leg order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchLegResult {
+    pub swap_pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub is_sol_to_token: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,           // What the user received (after any transfer fee)
+    pub fee_amount: u64,           // Swap fee in lamports
+}
+
+/// Result of quote_swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapQuote {
+    pub amount_out: u64,           // What the user would receive (after any transfer fee)
+    pub fee_amount: u64,           // Swap fee in lamports
+    pub fee_rate: u16,             // Fee rate charged right now in bps
+    pub price_impact_bps: u64,     // Spot price move caused by the trade
+    pub post_trade_price: u64,     // Spot price after the trade, scaled by 1e9
+    pub blocked_by_status: bool,   // Buy of a dead token, would be rejected
+    pub blocked_by_lock: bool,     // Pool is locked by a running flash swap
+    pub blocked_by_reserves: bool, // Booked reserves drifted from the pool's balances
+    pub blocked_by_circuit_breaker: bool, // Circuit breaker halts this direction
+    pub blocked_by_launch_limit: bool, // Buy exceeds the launch window wallet limits
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PriceObservation {
+    pub timestamp: i64,            // When the observation was taken
+    pub price_cumulative: u128,    // Accumulator value at that time
+}
+
+impl SwapPool {
+    /// Account size including the discriminator. Fields are only ever appended,
+    /// so migrate_pool can grow pools created with an older layout.
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 32 + 8 + 2 + 1 + 8 + 1 + 8 + 16 + 8 + 1
+        + (TWAP_OBSERVATIONS * (8 + 16)) + 1 + (8 + 8 + 8 + 2) + 1 + 16
+        + (2 + 8 + 8 + 8 + 1 + 1) + (2 + 2 + 8 + 2 + 2);
+
+    /// Set up a freshly created pool; reserves and LP supply are set by the caller
+    pub fn initialize(
+        &mut self,
+        token_mint: Pubkey,
+        lp_mint: Pubkey,
+        bump: u8,
+        now: i64,
+        quote_mode: QuoteMode,
+        launch_protection: LaunchProtection
+    ) {
+        self.token_mint = token_mint;
+        self.lp_mint = lp_mint;
+        self.fee_rate = 30; // 0.3% fee (30 basis points)
+        self.is_active = true;
+        self.created_at = now;
+        self.bump = bump;
+        self.price_cumulative = 0;
+        self.last_price_update = now;
+        self.observation_index = 0;
+        self.total_buys = 0;
+        self.observations = [PriceObservation::default(); TWAP_OBSERVATIONS];
+        self.observations[0] = PriceObservation {
+            timestamp: now,
+            price_cumulative: 0,
+        };
+        self.quote_mode = quote_mode;
+        self.launch_protection = launch_protection;
+        self.locked = false;
+        self.fee_growth_per_share = 0;
+        self.circuit_breaker = CircuitBreaker::default();
+    }
+
+    /// Whether the anti-sniper launch window is still running
+    pub fn launch_protection_active(&self, now: i64) -> bool {
+        now < self.created_at.saturating_add(self.launch_protection.duration)
+    }
+
+    /// Fee rate charged at `now`: the launch fee decays linearly to the dynamic
+    /// fee over the protection window, and the result stays within the fee
+    /// schedule's bounds
+    pub fn current_fee_rate(&self, now: i64) -> u16 {
+        let mut fee_rate = self.dynamic_fee_rate(now);
+        let protection = &self.launch_protection;
+        let launch_fee_rate = protection.launch_fee_rate as u64;
+        if launch_fee_rate > fee_rate && self.launch_protection_active(now) {
+            let remaining = (self.created_at.saturating_add(protection.duration) - now) as u64;
+            fee_rate += (launch_fee_rate - fee_rate) * remaining / protection.duration as u64;
+        }
+
+        let schedule = &self.fee_schedule;
+        fee_rate.max(schedule.min_fee_rate as u64).min(schedule.max_fee_rate as u64) as u16
+    }
+
+    /// fee_rate plus the young pool premium, which decays linearly to zero over
+    /// young_pool_age, and the volatility premium. Not yet bounded.
+    pub fn dynamic_fee_rate(&self, now: i64) -> u64 {
+        let schedule = &self.fee_schedule;
+        let age = now.saturating_sub(self.created_at).max(0);
+        let age_premium = if age < schedule.young_pool_age {
+            schedule.young_pool_fee_rate as u64 * (schedule.young_pool_age - age) as u64 / schedule.young_pool_age as u64
+        } else {
+            0
+        };
+        let volatility_premium = self
+            .realized_volatility_bps()
+            .saturating_mul(schedule.volatility_fee_share as u64) / math::BPS_DENOMINATOR;
+
+        (self.fee_rate as u64 + age_premium).saturating_add(volatility_premium)
+    }
+
+    /// Mean move in bps between the average prices of consecutive observation
+    /// intervals, 0 until there are at least two intervals
+    pub fn realized_volatility_bps(&self) -> u64 {
+        let mut previous: Option<PriceObservation> = None;
+        let mut previous_price: Option<u64> = None;
+        let mut total_moves = 0u64;
+        let mut count = 0u64;
+
+        // Walk the ring buffer oldest first
+        let start = self.observation_index as usize + 1;
+        for i in 0..TWAP_OBSERVATIONS {
+            let observation = self.observations[(start + i) % TWAP_OBSERVATIONS];
+            if observation.timestamp == 0 {
+                continue;
+            }
+
+            if let Some(last) = previous {
+                if observation.timestamp > last.timestamp {
+                    let elapsed = (observation.timestamp - last.timestamp) as u128;
+                    let price = (observation.price_cumulative.wrapping_sub(last.price_cumulative) / elapsed) as u64;
+                    if let Some(last_price) = previous_price {
+                        let move_bps = price_impact_bps(last_price, price).unwrap_or(u64::MAX);
+                        total_moves = total_moves.saturating_add(move_bps);
+                        count += 1;
+                    }
+                    previous_price = Some(price);
+                }
+            }
+            previous = Some(observation);
+        }
+
+        if count == 0 {
+            return 0;
+        }
+        total_moves / count
+    }
+
+    /// Spot price in SOL per token, scaled by 1e9
+    pub fn spot_price(&self) -> u64 {
+        calculate_spot_price(self.sol_reserve, self.token_reserve)
+    }
+
+    /// Accumulator value at `now`, including time elapsed since the last update
+    pub fn current_price_cumulative(&self, now: i64) -> u128 {
+        let elapsed = now.saturating_sub(self.last_price_update).max(0) as u128;
+        self.price_cumulative
+            .wrapping_add((self.spot_price() as u128).wrapping_mul(elapsed))
+    }
+
+    /// Start a new circuit breaker window if the last one has ended, then fail if
+    /// trades in this direction are halted
+    pub fn check_circuit_breaker(&mut self, slot: u64, is_sol_to_token: bool) -> Result<()> {
+        let halted = self.circuit_breaker_halts(slot, is_sol_to_token);
+        let spot_price = self.spot_price();
+        let breaker = &mut self.circuit_breaker;
+        if breaker.max_move_bps == 0 {
+            return Ok(());
+        }
+
+        if slot >= breaker.window_start_slot.saturating_add(breaker.window_slots.max(1)) {
+            breaker.window_start_slot = slot;
+            breaker.window_start_price = spot_price;
+            breaker.tripped_up = false;
+            breaker.tripped_down = false;
+        }
+
+        require!(!halted, SwapError::CircuitBreakerTripped);
+        Ok(())
+    }
+
+    /// Whether the circuit breaker halts trades in this direction at `slot`; a
+    /// tripped breaker clears once its window has passed
+    pub fn circuit_breaker_halts(&self, slot: u64, is_sol_to_token: bool) -> bool {
+        let breaker = &self.circuit_breaker;
+        if breaker.max_move_bps == 0
+            || slot >= breaker.window_start_slot.saturating_add(breaker.window_slots.max(1))
+        {
+            return false;
+        }
+
+        if is_sol_to_token { breaker.tripped_up } else { breaker.tripped_down }
+    }
+
+    /// Trip the circuit breaker in the direction the price moved if it is now more
+    /// than max_move_bps from the window's starting price. Returns the move in bps
+    /// when this call trips it.
+    pub fn record_price_move(&mut self) -> Result<Option<u64>> {
+        let spot_price = self.spot_price();
+        let breaker = &mut self.circuit_breaker;
+        if breaker.max_move_bps == 0 {
+            return Ok(None);
+        }
+
+        let move_bps = price_impact_bps(breaker.window_start_price, spot_price)?;
+        if move_bps <= breaker.max_move_bps as u64 {
+            return Ok(None);
+        }
+
+        let tripped = if spot_price > breaker.window_start_price {
+            &mut breaker.tripped_up
+        } else {
+            &mut breaker.tripped_down
+        };
+        if *tripped {
+            return Ok(None);
+        }
+
+        *tripped = true;
+        Ok(Some(move_bps))
+    }
+
+    /// (sol_reserve, token_reserve) once a priced swap settles: the trade moves
+    /// the reserves, the routed fee leaves the pool and the LP share stays in
+    fn reserves_after(&self, swap: &PricedSwap, lp_fee: u64) -> Result<(u64, u64)> {
+        let routed_fee = math::sub(swap.fee_amount, lp_fee)?;
+        if swap.is_sol_to_token {
+            Ok((
+                math::add(self.sol_reserve, math::sub(swap.amount_in, routed_fee)?)?,
+                math::sub(self.token_reserve, swap.amount_out)?,
+            ))
+        } else {
+            Ok((
+                math::sub(self.sol_reserve, math::add(swap.amount_out, routed_fee)?)?,
+                math::add(self.token_reserve, swap.pool_amount_in)?,
+            ))
+        }
+    }
+
+    /// Credit the LP share of a trading fee to every LP token
+    pub fn accrue_fee(&mut self, fee_amount: u64) {
+        self.fee_growth_per_share = self
+            .fee_growth_per_share
+            .wrapping_add(math::fee_growth_per_share(fee_amount, self.total_lp_supply));
+    }
+
+    /// Fail if either booked reserve is more than RESERVE_TOLERANCE_BPS away from
+    /// the pool's actual balance
+    pub fn check_reserves(&self, token_balance: u64, sol_balance: u64) -> Result<()> {
+        require!(self.reserves_in_sync(token_balance, sol_balance)?, SwapError::ReservesOutOfSync);
+        Ok(())
+    }
+
+    /// Whether both booked reserves are within RESERVE_TOLERANCE_BPS of the pool's
+    /// actual balances
+    pub fn reserves_in_sync(&self, token_balance: u64, sol_balance: u64) -> Result<bool> {
+        let in_sync = |booked: u64, actual: u64| -> Result<bool> {
+            Ok(booked.abs_diff(actual) <= math::mul_div(booked, RESERVE_TOLERANCE_BPS, math::BPS_DENOMINATOR)?)
+        };
+
+        Ok(in_sync(self.token_reserve, token_balance)? && in_sync(self.sol_reserve, sol_balance)?)
+    }
+
+    /// Time-weighted average price over at least `window_seconds` before `now`
+    pub fn twap(&self, now: i64, window_seconds: i64) -> Result<u64> {
+        let target = now - window_seconds;
+
+        // Newest stored observation that is at least `window_seconds` old
+        let observation = self
+            .observations
+            .iter()
+            .filter(|o| o.timestamp > 0 && o.timestamp <= target)
+            .max_by_key(|o| o.timestamp)
+            .ok_or(SwapError::InsufficientPriceHistory)?;
+
+        let elapsed = now - observation.timestamp;
+        let price_delta = self
+            .current_price_cumulative(now)
+            .wrapping_sub(observation.price_cumulative);
+
+        Ok((price_delta / elapsed as u128) as u64)
+    }
+
+    /// Accumulate the price that held since the last update. Must be called
+    /// BEFORE reserves change so a trade only affects the TWAP once time passes.
+    pub fn update_price_accumulator(&mut self, now: i64) {
+        if now <= self.last_price_update {
+            return;
+        }
+
+        self.price_cumulative = self.current_price_cumulative(now);
+        self.last_price_update = now;
+
+        let last = self.observations[self.observation_index as usize];
+        if now - last.timestamp >= TWAP_OBSERVATION_INTERVAL {
+            let next = (self.observation_index as usize + 1) % TWAP_OBSERVATIONS;
+            self.observations[next] = PriceObservation {
+                timestamp: now,
+                price_cumulative: self.price_cumulative,
+            };
+            self.observation_index = next as u8;
+        }
+    }
+}
+
+// ===== EVENTS =====
+
+#[event]
+pub struct SwapExecuted {
+    pub swap_pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub trader: Pubkey,
+    pub is_sol_to_token: bool,
+    pub amount_in: u64,            // Amount the trader paid in
+    pub amount_out: u64,           // Amount the trader received
+    pub fee_amount: u64,           // Trading fee in SOL
+    pub sol_reserve: u64,          // Pool reserves after the trade
+    pub token_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub swap_pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub provider: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub lp_tokens: u64,            // LP tokens minted to the provider
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub swap_pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub provider: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub lp_tokens: u64,            // LP tokens burned
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub swap_pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub window_start_price: u64,
+    pub price: u64,                // Spot price after the tripping trade
+    pub move_bps: u64,
+    pub halts_buys: bool,
+    pub halts_sells: bool,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReservesSynced {
+    pub swap_pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_reserve: u64,        // Reserves after the sync
+    pub sol_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralFeesClaimed {
+    pub referrer: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolDisabled {
+    pub swap_pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,         // lp_custody vault or protocol admin
+    pub timestamp: i64,
 }
 
 #[error_code]
@@ -487,4 +4804,58 @@ pub enum SwapError {
     InsufficientLiquidity,
     #[msg("Mathematical overflow occurred.")]
     MathOverflow,
+    #[msg("Routed swap must use two different pools.")]
+    InvalidRoute,
+    #[msg("Not enough price history for the requested TWAP window.")]
+    InsufficientPriceHistory,
+    #[msg("Token is dead - buys are disabled. Sells are still allowed.")]
+    BuysDisabledForDeadToken,
+    #[msg("Signer is not allowed to perform this action.")]
+    Unauthorized,
+    #[msg("Wrapped-SOL pool accounts are missing.")]
+    MissingWsolAccounts,
+    #[msg("Only native SOL pools support this operation.")]
+    UnsupportedQuoteMode,
+    #[msg("Initial deposit is too small to lock the minimum liquidity.")]
+    InsufficientInitialLiquidity,
+    #[msg("Buys during the launch window require the buyer guard account.")]
+    BuyerGuardRequired,
+    #[msg("Launch window buy limit exceeded.")]
+    LaunchBuyLimitExceeded,
+    #[msg("Transaction landed after its deadline.")]
+    DeadlineExceeded,
+    #[msg("Price impact exceeds the allowed maximum.")]
+    PriceImpactExceeded,
+    #[msg("Pool is locked by an in-progress flash swap.")]
+    PoolLocked,
+    #[msg("Flash swap was not repaid.")]
+    FlashSwapNotRepaid,
+    #[msg("Pool reserves are out of sync with its balances; sync or skim the pool first.")]
+    ReservesOutOfSync,
+    #[msg("Circuit breaker tripped: trading in this direction is halted for now.")]
+    CircuitBreakerTripped,
+    #[msg("Traders can't refer themselves.")]
+    SelfReferral,
+    #[msg("Referrer is not the one the trader registered with.")]
+    ReferrerMismatch,
+    #[msg("Invalid limit order parameters.")]
+    InvalidOrder,
+    #[msg("Limit order has expired.")]
+    OrderExpired,
+    #[msg("Pool price has not reached the order's target.")]
+    OrderNotTriggered,
+    #[msg("Batch legs don't match the accounts supplied, or there are too many.")]
+    InvalidBatch,
+    #[msg("Pool account already has the current layout.")]
+    PoolUpToDate,
+    #[msg("Account is not a swap pool of this program.")]
+    InvalidPoolAccount,
+    #[msg("Invalid DCA schedule.")]
+    InvalidDcaSchedule,
+    #[msg("The DCA vault's next slice isn't due yet.")]
+    DcaSliceNotDue,
+    #[msg("The DCA vault has executed all of its slices.")]
+    DcaComplete,
+    #[msg("Slice would fill above the DCA vault's max price.")]
+    DcaPriceAboveMax,
 }
\ No newline at end of file
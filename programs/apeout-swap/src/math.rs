@@ -0,0 +1,169 @@
+//! Integer-only AMM math. Intermediate products are taken in u128 and every
+//! operation is checked, failing with `SwapError::MathOverflow`.
+
+use anchor_lang::prelude::*;
+
+use crate::SwapError;
+
+/// LP tokens permanently locked on the first mint of a pool. They are counted in
+/// `total_lp_supply` but never minted, so the pool can never be fully drained and
+/// the first depositor cannot inflate the value of a single LP share.
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// Basis point denominator (10000 = 100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Checked u64 addition
+pub fn add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| SwapError::MathOverflow.into())
+}
+
+/// Checked u64 subtraction
+pub fn sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or_else(|| SwapError::MathOverflow.into())
+}
+
+/// `a * b / denominator`, rounded down
+pub fn mul_div(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, SwapError::MathOverflow);
+    to_u64(a as u128 * b as u128 / denominator as u128)
+}
+
+/// `a * b / denominator`, rounded up
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, SwapError::MathOverflow);
+    to_u64((a as u128 * b as u128).div_ceil(denominator as u128))
+}
+
+/// Narrow a u128 result back to u64
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| SwapError::MathOverflow.into())
+}
+
+/// Integer square root, rounded down (Newton's method)
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Start above the root so the iteration decreases monotonically
+    let mut x = 1u128 << ((128 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// LP supply created by the first deposit: sqrt(token_amount * sol_amount).
+/// Returns (total supply, amount to mint); the difference is MINIMUM_LIQUIDITY.
+pub fn initial_lp_supply(token_amount: u64, sol_amount: u64) -> Result<(u64, u64)> {
+    let total = to_u64(isqrt(token_amount as u128 * sol_amount as u128))?;
+    require!(total > MINIMUM_LIQUIDITY, SwapError::InsufficientInitialLiquidity);
+    Ok((total, total - MINIMUM_LIQUIDITY))
+}
+
+/// Q64.64 fee growth per LP token from charging `fee_amount` across `lp_supply`
+pub fn fee_growth_per_share(fee_amount: u64, lp_supply: u64) -> u128 {
+    if lp_supply == 0 {
+        return 0;
+    }
+    ((fee_amount as u128) << 64) / lp_supply as u128
+}
+
+/// Fees earned by `lp_amount` LP tokens over a Q64.64 fee growth delta, rounded down.
+/// The delta is split so the product can't overflow u128.
+pub fn fees_for_shares(lp_amount: u64, growth_delta: u128) -> Result<u64> {
+    let lp_amount = lp_amount as u128;
+    let whole = lp_amount * (growth_delta >> 64);
+    let fraction = (lp_amount * (growth_delta & u64::MAX as u128)) >> 64;
+    to_u64(whole.checked_add(fraction).ok_or(SwapError::MathOverflow)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isqrt_small_values() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(2), 1);
+        assert_eq!(isqrt(3), 1);
+        assert_eq!(isqrt(4), 2);
+    }
+
+    #[test]
+    fn isqrt_rounds_down_between_squares() {
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(17), 4);
+        assert_eq!(isqrt(1_000_000_000_000_000_000), 1_000_000_000);
+        assert_eq!(isqrt(1_000_000_000_000_000_001), 1_000_000_000);
+        assert_eq!(isqrt(999_999_999_999_999_999), 999_999_999);
+    }
+
+    #[test]
+    fn isqrt_u128_max() {
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt((u64::MAX as u128) * (u64::MAX as u128)), u64::MAX as u128);
+    }
+
+    #[test]
+    fn mul_div_ceil_rounds_up_only_with_remainder() {
+        assert_eq!(mul_div_ceil(10, 10, 5).unwrap(), 20);
+        assert_eq!(mul_div_ceil(10, 10, 3).unwrap(), 34);
+        assert_eq!(mul_div(10, 10, 3).unwrap(), 33);
+        assert_eq!(mul_div_ceil(0, 10, 3).unwrap(), 0);
+    }
+
+    #[test]
+    fn mul_div_ceil_rejects_zero_denominator_and_overflow() {
+        assert!(mul_div_ceil(1, 1, 0).is_err());
+        assert!(mul_div_ceil(u64::MAX, 2, 1).is_err());
+        assert_eq!(mul_div_ceil(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn initial_lp_supply_locks_minimum_liquidity() {
+        assert_eq!(initial_lp_supply(1_001, 1_001).unwrap(), (1_001, 1));
+        // Non-perfect square: sqrt(2_000_000) = 1414.2
+        assert_eq!(initial_lp_supply(2, 1_000_000).unwrap(), (1_414, 414));
+        assert_eq!(
+            initial_lp_supply(u64::MAX, u64::MAX).unwrap(),
+            (u64::MAX, u64::MAX - MINIMUM_LIQUIDITY)
+        );
+    }
+
+    #[test]
+    fn initial_lp_supply_rejects_deposit_at_or_below_minimum() {
+        assert!(initial_lp_supply(1_000, 1_000).is_err());
+        assert!(initial_lp_supply(1, 999_999).is_err());
+        assert!(initial_lp_supply(0, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn fees_for_shares_whole_and_fractional_growth() {
+        let one_lamport = 1u128 << 64;
+        assert_eq!(fees_for_shares(5, one_lamport).unwrap(), 5);
+        assert_eq!(fees_for_shares(3, one_lamport / 2).unwrap(), 1);
+        assert_eq!(fees_for_shares(0, u128::MAX).unwrap(), 0);
+        assert_eq!(fees_for_shares(u64::MAX, one_lamport).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn fees_for_shares_rejects_overflow() {
+        assert!(fees_for_shares(u64::MAX, 2u128 << 64).is_err());
+        assert!(fees_for_shares(2, u128::MAX).is_err());
+    }
+
+    #[test]
+    fn fee_growth_round_trips_through_fees_for_shares() {
+        let growth = fee_growth_per_share(1_000, 4_000);
+        assert_eq!(fees_for_shares(4_000, growth).unwrap(), 1_000);
+        assert_eq!(fees_for_shares(1_000, growth).unwrap(), 250);
+        assert_eq!(fee_growth_per_share(1_000, 0), 0);
+    }
+}
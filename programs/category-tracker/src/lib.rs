@@ -1,7 +1,17 @@
 use anchor_lang::prelude::*;
+use protocol_config::ProtocolConfig;
 
 declare_id!("FWVc9huqjX9XbPJ8BZ1KFmz572pyqXRtsF5gFRVTEx97");
 
+/// Swap program whose pools are the only source of trade stats
+pub mod apeout_swap_program {
+    use super::*;
+    declare_id!("SwApCoNtRaCt1111111111111111111111111111111");
+}
+
+/// Length of a tracking day; day ids count days since the Unix epoch
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
 
 #[program]
 pub mod category_tracker {
@@ -32,9 +42,9 @@ pub mod category_tracker {
     /// Initialize token volume tracker
     pub fn initialize_token_tracker(
         ctx: Context<InitializeTokenTracker>,
-        token_mint: Pubkey,
         creator: Pubkey
     ) -> Result<()> {
+        let token_mint = ctx.accounts.token_mint.key();
         let tracker = &mut ctx.accounts.token_volume_tracker;
         tracker.token_mint = token_mint;
         tracker.creator = creator;
@@ -116,6 +126,7 @@ pub mod category_tracker {
     }
 
     /// Track token creation and trading volume for Most Traded Token category
+    /// (only callable by the token's swap pool via CPI)
     pub fn record_token_trade(
         ctx: Context<RecordTokenTrade>,
         volume_delta: u64
@@ -128,7 +139,8 @@ pub mod category_tracker {
         
         // Update daily leaderboard if this is now the highest
         if token_tracker.total_volume > daily_tracker.most_traded_volume {
-            daily_tracker.most_traded_token_leader = Some(token_tracker.creator);
+            let day_id = daily_tracker.day_id;
+            set_leader(&mut daily_tracker.most_traded_token_leader, token_tracker.creator, day_id, 0, token_tracker.total_volume)?;
             daily_tracker.most_traded_volume = token_tracker.total_volume;
         }
         
@@ -137,7 +149,8 @@ pub mod category_tracker {
         Ok(())
     }
 
-    /// Track LP provision for LP MVP category
+    /// Track LP provision for LP MVP category; `liquidity_added` is the SOL-equivalent
+    /// value of the deposit (only callable by the token's swap pool via CPI)
     pub fn record_lp_provision(
         ctx: Context<RecordLPProvision>,
         liquidity_added: u64
@@ -150,7 +163,8 @@ pub mod category_tracker {
         
         // Update daily leaderboard if this is now the highest
         if lp_tracker.total_liquidity > daily_tracker.lp_mvp_amount {
-            daily_tracker.lp_mvp_leader = Some(lp_tracker.provider);
+            let day_id = daily_tracker.day_id;
+            set_leader(&mut daily_tracker.lp_mvp_leader, lp_tracker.provider, day_id, 1, lp_tracker.total_liquidity)?;
             daily_tracker.lp_mvp_amount = lp_tracker.total_liquidity;
         }
         
@@ -160,6 +174,7 @@ pub mod category_tracker {
     }
 
     /// Track early buying for Early Buyer category
+    /// (only callable by the token's swap pool via CPI)
     pub fn record_early_buy(
         ctx: Context<RecordEarlyBuy>,
         purchase_order: u32
@@ -178,7 +193,8 @@ pub mod category_tracker {
         
         // Update daily leaderboard if this buyer now has the most points
         if early_tracker.total_points > daily_tracker.early_buyer_points {
-            daily_tracker.early_buyer_leader = Some(early_tracker.buyer);
+            let day_id = daily_tracker.day_id;
+            set_leader(&mut daily_tracker.early_buyer_leader, early_tracker.buyer, day_id, 2, early_tracker.total_points as u64)?;
             daily_tracker.early_buyer_points = early_tracker.total_points;
         }
         
@@ -188,7 +204,7 @@ pub mod category_tracker {
         Ok(())
     }
 
-    /// Track trading performance for Smart Money category
+    /// Track trading performance for Smart Money category (admin only)
     pub fn record_trade_outcome(
         ctx: Context<RecordTradeOutcome>,
         is_profitable: bool,
@@ -200,10 +216,10 @@ pub mod category_tracker {
         // Update trading stats
         if is_profitable {
             trading_tracker.wins += 1;
-            trading_tracker.total_profit += profit_loss.abs() as u64;
+            trading_tracker.total_profit += profit_loss.unsigned_abs();
         } else {
             trading_tracker.losses += 1;
-            trading_tracker.total_loss += profit_loss.abs() as u64;
+            trading_tracker.total_loss += profit_loss.unsigned_abs();
         }
         
         // Calculate win rate (only if minimum 5 trades)
@@ -214,7 +230,8 @@ pub mod category_tracker {
             
             // Update daily leaderboard if this is now the highest win rate
             if win_rate > daily_tracker.smart_money_win_rate {
-                daily_tracker.smart_money_leader = Some(trading_tracker.trader);
+                let day_id = daily_tracker.day_id;
+                set_leader(&mut daily_tracker.smart_money_leader, trading_tracker.trader, day_id, 3, win_rate as u64)?;
                 daily_tracker.smart_money_win_rate = win_rate;
             }
         }
@@ -225,6 +242,7 @@ pub mod category_tracker {
     }
 
     /// Track profit for Profit Champion category
+    /// (only callable by the token's swap pool via CPI)
     pub fn record_profit(
         ctx: Context<RecordProfit>,
         buy_amount: u64,
@@ -243,7 +261,8 @@ pub mod category_tracker {
         
         // Update daily leaderboard if this is now the highest profit (and positive)
         if net_profit > 0 && net_profit > daily_tracker.profit_champion_amount {
-            daily_tracker.profit_champion_leader = Some(profit_tracker.trader);
+            let day_id = daily_tracker.day_id;
+            set_leader(&mut daily_tracker.profit_champion_leader, profit_tracker.trader, day_id, 4, net_profit as u64)?;
             daily_tracker.profit_champion_amount = net_profit;
         }
         
@@ -265,6 +284,36 @@ pub mod category_tracker {
     }
 }
 
+// Helper function to update a category leader, emitting CategoryLeaderChanged when
+// someone new takes the lead. Categories are numbered as in daily_game_vault.
+fn set_leader(
+    leader: &mut Option<Pubkey>,
+    new_leader: Pubkey,
+    day_id: u64,
+    category: u8,
+    metric_value: u64
+) -> Result<()> {
+    let previous_leader = leader.replace(new_leader);
+
+    if previous_leader != Some(new_leader) {
+        emit!(CategoryLeaderChanged {
+            day_id,
+            category,
+            previous_leader,
+            new_leader,
+            metric_value,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+// Helper function to get the id of the current tracking day
+fn current_day_id() -> Result<u64> {
+    Ok((Clock::get()?.unix_timestamp / SECONDS_PER_DAY) as u64)
+}
+
 // ===== ACCOUNT CONTEXTS =====
 
 #[derive(Accounts)]
@@ -366,26 +415,86 @@ pub struct InitializeProfitTracker<'info> {
 
 #[derive(Accounts)]
 pub struct RecordTokenTrade<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"token_volume", token_volume_tracker.token_mint.as_ref()],
+        bump
+    )]
     pub token_volume_tracker: Account<'info, TokenVolumeTracker>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"daily_tracker", daily_tracker.day_id.to_le_bytes().as_ref()],
+        bump = daily_tracker.bump,
+        constraint = daily_tracker.day_id == current_day_id()? @ CategoryError::WrongDay
+    )]
     pub daily_tracker: Account<'info, DailyTracker>,
+    /// The token's swap pool, signing through apeout_swap
+    #[account(
+        seeds = [b"swap_pool", token_volume_tracker.token_mint.as_ref()],
+        bump,
+        seeds::program = apeout_swap_program::ID
+    )]
+    pub swap_pool: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct RecordLPProvision<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"lp_tracker", provider.key().as_ref(), daily_tracker.day_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = lp_tracker.provider == provider.key() @ CategoryError::TrackerMismatch
+    )]
     pub lp_tracker: Account<'info, LPTracker>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"daily_tracker", daily_tracker.day_id.to_le_bytes().as_ref()],
+        bump = daily_tracker.bump,
+        constraint = daily_tracker.day_id == current_day_id()? @ CategoryError::WrongDay
+    )]
     pub daily_tracker: Account<'info, DailyTracker>,
+    /// The pool the liquidity went into, signing through apeout_swap
+    #[account(
+        seeds = [b"swap_pool", token_mint.key().as_ref()],
+        bump,
+        seeds::program = apeout_swap_program::ID
+    )]
+    pub swap_pool: Signer<'info>,
+    /// CHECK: Mint of the pool's token, only used to derive the swap pool
+    pub token_mint: AccountInfo<'info>,
+    pub provider: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct RecordEarlyBuy<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            b"early_buy",
+            buyer.key().as_ref(),
+            early_buy_tracker.token_mint.as_ref(),
+            daily_tracker.day_id.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = early_buy_tracker.buyer == buyer.key() @ CategoryError::TrackerMismatch
+    )]
     pub early_buy_tracker: Account<'info, EarlyBuyTracker>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"daily_tracker", daily_tracker.day_id.to_le_bytes().as_ref()],
+        bump = daily_tracker.bump,
+        constraint = daily_tracker.day_id == current_day_id()? @ CategoryError::WrongDay
+    )]
     pub daily_tracker: Account<'info, DailyTracker>,
+    /// The token's swap pool, signing through apeout_swap
+    #[account(
+        seeds = [b"swap_pool", early_buy_tracker.token_mint.as_ref()],
+        bump,
+        seeds::program = apeout_swap_program::ID
+    )]
+    pub swap_pool: Signer<'info>,
+    /// CHECK: Buyer credited with the buy; the swap pool's signature vouches for it
+    pub buyer: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -394,14 +503,39 @@ pub struct RecordTradeOutcome<'info> {
     pub trading_tracker: Account<'info, TradingTracker>,
     #[account(mut)]
     pub daily_tracker: Account<'info, DailyTracker>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(constraint = protocol_config.is_admin(&authority.key()) @ CategoryError::Unauthorized)]
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct RecordProfit<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"profit", trader.key().as_ref(), daily_tracker.day_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = profit_tracker.trader == trader.key() @ CategoryError::TrackerMismatch
+    )]
     pub profit_tracker: Account<'info, ProfitTracker>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"daily_tracker", daily_tracker.day_id.to_le_bytes().as_ref()],
+        bump = daily_tracker.bump,
+        constraint = daily_tracker.day_id == current_day_id()? @ CategoryError::WrongDay
+    )]
     pub daily_tracker: Account<'info, DailyTracker>,
+    /// The traded token's swap pool, signing through apeout_swap
+    #[account(
+        seeds = [b"swap_pool", token_mint.key().as_ref()],
+        bump,
+        seeds::program = apeout_swap_program::ID
+    )]
+    pub swap_pool: Signer<'info>,
+    /// CHECK: Mint of the traded token, only used to derive the swap pool
+    pub token_mint: AccountInfo<'info>,
+    /// CHECK: Trader credited with the trade; the swap pool's signature vouches for it
+    pub trader: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -483,10 +617,28 @@ pub struct DailyLeaders {
     pub profit_champion: Option<Pubkey>,
 }
 
+// ===== EVENTS =====
+
+#[event]
+pub struct CategoryLeaderChanged {
+    pub day_id: u64,
+    pub category: u8,
+    pub previous_leader: Option<Pubkey>,
+    pub new_leader: Pubkey,
+    pub metric_value: u64,
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum CategoryError {
     #[msg("Purchase order too high for early buyer category (max 10).")]
     TooLateForEarlyBuyer,
     #[msg("Minimum 5 trades required for Smart Money category.")]
     InsufficientTrades,
+    #[msg("Tracker does not belong to this trader.")]
+    TrackerMismatch,
+    #[msg("Signer is not the protocol admin.")]
+    Unauthorized,
+    #[msg("Daily tracker is not for the current day.")]
+    WrongDay,
 }
\ No newline at end of file
@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
+use protocol_config::ProtocolConfig;
 
 declare_id!("5DhpMM7Qbi7HK8H2LJJjvBqLCmasQVwqirexo61UpikF");
 
+/// Fee program that credits game rewards through its fee authority PDA
+pub mod fee_rewards_program {
+    use super::*;
+    declare_id!("BHYV3jQD32izKpfatST8kVtJ6Mcdwe9S7xgUDBUkQaqU");
+}
+
 #[program]
 pub mod daily_game_vault {
     use super::*;
@@ -24,7 +31,7 @@ pub mod daily_game_vault {
         Ok(())
     }
 
-    /// Manual record activity (for backend updates)
+    /// Manual record activity (for backend updates, admin only)
     pub fn record_activity(
         ctx: Context<RecordActivity>,
         category: u8,
@@ -32,14 +39,27 @@ pub mod daily_game_vault {
         metric_value: u64
     ) -> Result<()> {
         let vault = &mut ctx.accounts.daily_game_vault;
+        let day_id = vault.day_id;
         
-        match category {
-            0 => vault.most_traded_token = Some(participant),
-            1 => vault.lp_mvp = Some(participant),
-            2 => vault.early_buyer = Some(participant),
-            3 => vault.smart_money = Some(participant),
-            4 => vault.profit_champion = Some(participant),
+        let winner = match category {
+            0 => &mut vault.most_traded_token,
+            1 => &mut vault.lp_mvp,
+            2 => &mut vault.early_buyer,
+            3 => &mut vault.smart_money,
+            4 => &mut vault.profit_champion,
             _ => return Err(GameError::InvalidCategory.into()),
+        };
+        let previous_leader = winner.replace(participant);
+
+        if previous_leader != Some(participant) {
+            emit!(CategoryLeaderChanged {
+                day_id,
+                category,
+                previous_leader,
+                new_leader: participant,
+                metric_value,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
         }
 
         msg!("Category {} winner updated: {} with value {}", category, participant, metric_value);
@@ -92,6 +112,14 @@ pub mod daily_game_vault {
         claim_record.amount = reward_amount;
         claim_record.claim_time = Clock::get()?.unix_timestamp;
 
+        emit!(GameRewardClaimed {
+            day_id: vault.day_id,
+            category,
+            user: claimer,
+            amount: reward_amount,
+            timestamp: claim_record.claim_time,
+        });
+
         msg!("Category {} winner {} claimed {} lamports", category, claimer, reward_amount);
         Ok(())
     }
@@ -115,7 +143,7 @@ pub mod daily_game_vault {
         Ok(winner)
     }
 
-    /// Add rewards to the vault (called by fee_rewards contract)
+    /// Add rewards to the vault (called by fee_rewards contract or the admin)
     pub fn add_rewards(ctx: Context<AddRewards>, amount: u64) -> Result<()> {
         let vault = &mut ctx.accounts.daily_game_vault;
         vault.total_rewards += amount;
@@ -139,6 +167,9 @@ pub struct InitializeDay<'info> {
 pub struct RecordActivity<'info> {
     #[account(mut, seeds = [b"daily_game", daily_game_vault.day_id.to_le_bytes().as_ref()], bump = daily_game_vault.bump)]
     pub daily_game_vault: Account<'info, DailyGameVault>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(constraint = protocol_config.is_admin(&authority.key()) @ GameError::Unauthorized)]
     pub authority: Signer<'info>,
 }
 
@@ -169,7 +200,19 @@ pub struct GetCategoryWinner<'info> {
 pub struct AddRewards<'info> {
     #[account(mut, seeds = [b"daily_game", daily_game_vault.day_id.to_le_bytes().as_ref()], bump = daily_game_vault.bump)]
     pub daily_game_vault: Account<'info, DailyGameVault>,
-    pub authority: Signer<'info>, // Should be fee_rewards program
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        constraint = protocol_config.is_admin(&authority.key()) || is_fee_authority(&authority.key())
+            @ GameError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+// fee_rewards signs reward credits with its [b"fee_authority"] PDA
+fn is_fee_authority(key: &Pubkey) -> bool {
+    let (fee_authority, _) = Pubkey::find_program_address(&[b"fee_authority"], &fee_rewards_program::ID);
+    *key == fee_authority
 }
 
 #[account]
@@ -193,6 +236,25 @@ pub struct GameClaimRecord {
     pub claim_time: i64,
 }
 
+#[event]
+pub struct CategoryLeaderChanged {
+    pub day_id: u64,
+    pub category: u8,
+    pub previous_leader: Option<Pubkey>,
+    pub new_leader: Pubkey,
+    pub metric_value: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GameRewardClaimed {
+    pub day_id: u64,
+    pub category: u8,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum GameError {
     #[msg("You are not the winner in this category.")]
@@ -203,4 +265,6 @@ pub enum GameError {
     AlreadyClaimed,
     #[msg("No rewards available in vault.")]
     NoRewardsAvailable,
+    #[msg("Signer is not allowed to perform this action.")]
+    Unauthorized,
 }
\ No newline at end of file
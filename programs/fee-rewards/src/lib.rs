@@ -1,7 +1,20 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::solana_program::keccak;
+use daily_game_vault::DailyGameVault;
+use protocol_config::ProtocolConfig;
 
 declare_id!("BHYV3jQD32izKpfatST8kVtJ6Mcdwe9S7xgUDBUkQaqU");
 
+/// Swap program whose pool SOL accounts pay trading fees
+pub mod apeout_swap_program {
+    use super::*;
+    declare_id!("SwApCoNtRaCt1111111111111111111111111111111");
+}
+
+/// Length of a rewards day; day ids count days since the Unix epoch
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
 #[program]
 pub mod fee_rewards {
     use super::*;
@@ -12,6 +25,20 @@ pub mod fee_rewards {
         pool.total_rewards = 0;
         pool.total_volume = 0;
         pool.bump = *ctx.bumps.get("rewards_pool").unwrap();
+        pool.claims_root = [0; 32];
+        pool.total_claimed = 0;
+        Ok(())
+    }
+
+    /// Publish the holder reward snapshot of a finished day (admin only). Each leaf
+    /// is keccak(user || amount as u64 LE), with the staking boost already applied.
+    pub fn set_claims_root(ctx: Context<SetClaimsRoot>, claims_root: [u8; 32]) -> Result<()> {
+        let pool = &mut ctx.accounts.rewards_pool;
+        require!(pool.day_id < current_day_id()?, FeeRewardError::DayNotOver);
+        require!(pool.claims_root == [0; 32], FeeRewardError::ClaimsRootAlreadySet);
+        pool.claims_root = claims_root;
+
+        msg!("Claims opened for day {}", pool.day_id);
         Ok(())
     }
 
@@ -21,63 +48,119 @@ pub mod fee_rewards {
         fee_amount: u64,
         trade_volume: u64
     ) -> Result<()> {
-        // Split: 75% holders, 15% games, 10% platform
-        let holder_cut = fee_amount * 75 / 100;
-        let game_cut = fee_amount * 15 / 100;
-        let platform_cut = fee_amount - holder_cut - game_cut; // remaining 10%
+        // Split per protocol config: holders, games, platform gets the remainder
+        let config = &ctx.accounts.protocol_config;
+        let holder_cut = bps_share(fee_amount, config.holder_fee_bps)?;
+        let game_cut = bps_share(fee_amount, config.game_fee_bps)?;
+        let platform_cut = fee_amount - holder_cut - game_cut;
 
         // 1. Add to holders reward pool
+        ctx.accounts.pay_fee(ctx.accounts.rewards_pool.to_account_info(), holder_cut)?;
+
         let rewards_pool = &mut ctx.accounts.rewards_pool;
         rewards_pool.total_rewards += holder_cut;
         rewards_pool.total_volume += trade_volume;
 
         // 2. Add to daily game reward vault
-        let game_vault = &mut ctx.accounts.daily_game_vault;
-        game_vault.total_rewards += game_cut;
+        ctx.accounts.pay_fee(ctx.accounts.daily_game_vault.to_account_info(), game_cut)?;
+
+        let fee_authority_seeds = &[
+            b"fee_authority".as_ref(),
+            &[*ctx.bumps.get("fee_authority").unwrap()],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[fee_authority_seeds];
+
+        let cpi_accounts = daily_game_vault::cpi::accounts::AddRewards {
+            daily_game_vault: ctx.accounts.daily_game_vault.to_account_info(),
+            protocol_config: ctx.accounts.protocol_config.to_account_info(),
+            authority: ctx.accounts.fee_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.daily_game_vault_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        daily_game_vault::cpi::add_rewards(cpi_ctx, game_cut)?;
 
         // 3. Transfer to platform treasury
-        **ctx.accounts.platform_wallet.try_borrow_mut_lamports()? += platform_cut;
+        ctx.accounts.pay_fee(ctx.accounts.platform_wallet.to_account_info(), platform_cut)?;
+
+        emit!(FeeRecorded {
+            rewards_pool: ctx.accounts.rewards_pool.key(),
+            day_id: ctx.accounts.rewards_pool.day_id,
+            fee_amount,
+            trade_volume,
+            holder_cut,
+            game_cut,
+            platform_cut,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        msg!("Fee split: {}% holders ({}), {}% games ({}), {}% platform ({})", 
-             75, holder_cut, 15, game_cut, 10, platform_cut);
+        msg!("Fee split: {} holders, {} games, {} platform", 
+             holder_cut, game_cut, platform_cut);
 
         Ok(())
     }
 
-    pub fn claim_rewards(ctx: Context<ClaimReward>, user_token_balance: u64, total_supply: u64, apeout_staked: u64) -> Result<()> {
+    /// Claim a holder reward from a day's snapshot with a merkle proof of the amount
+    pub fn claim_rewards(ctx: Context<ClaimReward>, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
         if ctx.accounts.claim_record.claimed {
             return Err(FeeRewardError::AlreadyClaimed.into());
         }
 
-        let base_share = (user_token_balance as u128)
-            .checked_mul(ctx.accounts.rewards_pool.total_rewards as u128)
-            .unwrap()
-            / (total_supply as u128);
+        let pool = &ctx.accounts.rewards_pool;
+        require!(pool.claims_root != [0; 32], FeeRewardError::ClaimsNotOpen);
+
+        let leaf = keccak::hashv(&[ctx.accounts.user.key().as_ref(), &amount.to_le_bytes()]).0;
+        require!(verify_proof(&proof, pool.claims_root, leaf), FeeRewardError::InvalidProof);
 
-        let multiplier = get_multiplier(apeout_staked);
-        let boosted = (base_share as f64 * multiplier) as u64;
+        // Claims can never pay out more than the fees the day collected
+        let total_claimed = pool.total_claimed.checked_add(amount).ok_or(FeeRewardError::MathOverflow)?;
+        require!(total_claimed <= pool.total_rewards, FeeRewardError::InsufficientRewards);
 
+        ctx.accounts.rewards_pool.total_claimed = total_claimed;
         ctx.accounts.claim_record.claimed = true;
-        ctx.accounts.claim_record.amount = boosted;
+        ctx.accounts.claim_record.amount = amount;
 
-        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += boosted;
-        **ctx.accounts.rewards_pool.to_account_info().try_borrow_mut_lamports()? -= boosted;
+        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += amount;
+        **ctx.accounts.rewards_pool.to_account_info().try_borrow_mut_lamports()? -= amount;
+
+        emit!(RewardClaimed {
+            rewards_pool: ctx.accounts.rewards_pool.key(),
+            day_id: ctx.accounts.rewards_pool.day_id,
+            user: ctx.accounts.user.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
 }
 
-fn get_multiplier(staked: u64) -> f64 {
-    if staked >= 5000_000000 { 2.0 }
-    else if staked >= 1000_000000 { 1.5 }
-    else if staked >= 100_000000 { 1.1 }
-    else { 1.0 }
+// Helper function to take a basis point share of an amount, multiplying in u128
+fn bps_share(amount: u64, bps: u16) -> Result<u64> {
+    u64::try_from(amount as u128 * bps as u128 / 10000).map_err(|_| FeeRewardError::MathOverflow.into())
+}
+
+// Helper function to get the id of the current rewards day
+fn current_day_id() -> Result<u64> {
+    Ok((Clock::get()?.unix_timestamp / SECONDS_PER_DAY) as u64)
+}
+
+// Helper function to check a merkle proof; pairs are hashed in sorted order
+fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).0
+        } else {
+            keccak::hashv(&[sibling, &node]).0
+        }
+    });
+    computed == root
 }
 
 #[derive(Accounts)]
 #[instruction(day_id: u64)]
 pub struct InitializePool<'info> {
-    #[account(init, payer = payer, seeds = [b"reward", day_id.to_le_bytes().as_ref()], bump, space = 8 + 8 + 8 + 8 + 1)]
+    #[account(init, payer = payer, seeds = [b"reward", day_id.to_le_bytes().as_ref()], bump, space = 8 + 8 + 8 + 8 + 1 + 32 + 8)]
     pub rewards_pool: Account<'info, RewardsPool>,
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -86,15 +169,76 @@ pub struct InitializePool<'info> {
 
 #[derive(Accounts)]
 pub struct RecordFee<'info> {
-    #[account(mut, seeds = [b"reward", rewards_pool.day_id.to_le_bytes().as_ref()], bump = rewards_pool.bump)]
+    #[account(
+        mut,
+        seeds = [b"reward", rewards_pool.day_id.to_le_bytes().as_ref()],
+        bump = rewards_pool.bump,
+        constraint = rewards_pool.day_id == current_day_id()? @ FeeRewardError::WrongDay
+    )]
     pub rewards_pool: Account<'info, RewardsPool>,
     
-    #[account(mut, seeds = [b"daily_game", rewards_pool.day_id.to_le_bytes().as_ref()], bump)]
+    #[account(
+        mut,
+        seeds = [b"daily_game", rewards_pool.day_id.to_le_bytes().as_ref()],
+        bump = daily_game_vault.bump,
+        seeds::program = daily_game_vault::ID
+    )]
     pub daily_game_vault: Account<'info, DailyGameVault>,
     
-    /// CHECK: This is a platform-owned treasury account
-    #[account(mut)]
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    /// CHECK: Platform treasury from the protocol config
+    #[account(mut, address = protocol_config.platform_treasury)]
     pub platform_wallet: AccountInfo<'info>,
+    
+    /// Swap pool SOL account paying the fee (signed by the swap program)
+    #[account(
+        mut,
+        seeds = [b"pool_sol", token_mint.key().as_ref()],
+        bump,
+        seeds::program = apeout_swap_program::ID
+    )]
+    pub fee_payer: Signer<'info>,
+    
+    /// CHECK: Mint of the traded token, only used to derive the fee payer
+    pub token_mint: AccountInfo<'info>,
+    
+    /// CHECK: PDA that signs reward credits to the daily game vault
+    #[account(seeds = [b"fee_authority"], bump)]
+    pub fee_authority: AccountInfo<'info>,
+    
+    pub daily_game_vault_program: Program<'info, daily_game_vault::program::DailyGameVault>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RecordFee<'info> {
+    fn pay_fee(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: self.fee_payer.to_account_info(),
+                    to,
+                },
+            ),
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetClaimsRoot<'info> {
+    #[account(mut, seeds = [b"reward", rewards_pool.day_id.to_le_bytes().as_ref()], bump = rewards_pool.bump)]
+    pub rewards_pool: Account<'info, RewardsPool>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(constraint = protocol_config.is_admin(&admin.key()) @ FeeRewardError::Unauthorized)]
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -120,23 +264,55 @@ pub struct RewardsPool {
     pub total_rewards: u64,
     pub total_volume: u64,
     pub bump: u8,
+    pub claims_root: [u8; 32],     // Merkle root of the day's holder rewards (zero until published)
+    pub total_claimed: u64,        // Rewards paid out so far
 }
 
 #[account]
-pub struct DailyGameVault {
+pub struct ClaimRecord {
+    pub claimed: bool,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeeRecorded {
+    pub rewards_pool: Pubkey,
     pub day_id: u64,
-    pub total_rewards: u64,
-    pub bump: u8,
+    pub fee_amount: u64,
+    pub trade_volume: u64,
+    pub holder_cut: u64,
+    pub game_cut: u64,
+    pub platform_cut: u64,
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct ClaimRecord {
-    pub claimed: bool,
+#[event]
+pub struct RewardClaimed {
+    pub rewards_pool: Pubkey,
+    pub day_id: u64,
+    pub user: Pubkey,
     pub amount: u64,
+    pub timestamp: i64,
 }
 
 #[error_code]
 pub enum FeeRewardError {
     #[msg("Reward already claimed")]
     AlreadyClaimed,
+    #[msg("Mathematical overflow occurred")]
+    MathOverflow,
+    #[msg("Signer is not the protocol admin")]
+    Unauthorized,
+    #[msg("Rewards pool is not for the current day")]
+    WrongDay,
+    #[msg("Rewards day is not over yet")]
+    DayNotOver,
+    #[msg("Claims root is already set")]
+    ClaimsRootAlreadySet,
+    #[msg("Claims are not open for this day")]
+    ClaimsNotOpen,
+    #[msg("Invalid merkle proof")]
+    InvalidProof,
+    #[msg("Claim exceeds the remaining rewards")]
+    InsufficientRewards,
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, TransferChecked, Mint};
+use protocol_config::ProtocolConfig;
 
 declare_id!("HkMJu6JoeV2NEF5hos9kpttBNkvEMkURJcLiEWYvaWvv");
 
@@ -7,6 +8,7 @@ declare_id!("HkMJu6JoeV2NEF5hos9kpttBNkvEMkURJcLiEWYvaWvv");
 pub mod holder_distribution {
     use super::*;
 
+    /// Set up the LP distribution for a token (admin only)
     pub fn initialize_distribution(
         ctx: Context<InitializeDistribution>,
         total_distribution: u64
@@ -36,11 +38,12 @@ pub mod holder_distribution {
         let bump = ctx.accounts.distribution_state.bump;
         let token_mint_key = ctx.accounts.token_mint.key();
 
-        token::transfer(
+        token_interface::transfer_checked(
             ctx.accounts
                 .transfer_context()
                 .with_signer(&[&[b"dist", token_mint_key.as_ref(), &[bump]]]),
             user_share_u64,
+            ctx.accounts.lp_mint.decimals,
         )?;
 
         ctx.accounts.user_claim_record.claimed = true;
@@ -55,8 +58,10 @@ pub mod holder_distribution {
 pub struct InitializeDistribution<'info> {
     #[account(init, payer = payer, seeds = [b"dist", token_mint.key().as_ref()], bump, space = 8 + 32 + 8 + 8 + 8 + 1)]
     pub distribution_state: Account<'info, DistributionState>,
-    pub token_mint: Account<'info, Mint>,
-    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut, constraint = protocol_config.is_admin(&payer.key()) @ ErrorCode::Unauthorized)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
@@ -65,11 +70,14 @@ pub struct InitializeDistribution<'info> {
 pub struct ClaimLP<'info> {
     #[account(mut, seeds = [b"dist", token_mint.key().as_ref()], bump = distribution_state.bump)]
     pub distribution_state: Account<'info, DistributionState>,
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// Mint of the tokens being distributed
+    #[account(address = vault_token_account.mint)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init,
         payer = user,
@@ -80,14 +88,15 @@ pub struct ClaimLP<'info> {
     pub user_claim_record: Account<'info, ClaimRecord>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> ClaimLP<'info> {
-    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
             from: self.vault_token_account.to_account_info(),
+            mint: self.lp_mint.to_account_info(),
             to: self.user_token_account.to_account_info(),
             authority: self.distribution_state.to_account_info(),
         };
@@ -117,4 +126,6 @@ pub enum ErrorCode {
     NothingToClaim,
     #[msg("All rewards have been claimed.")]
     AllRewardsClaimed,
-}
\ No newline at end of file
+    #[msg("Signer is not the protocol admin.")]
+    Unauthorized,
+}
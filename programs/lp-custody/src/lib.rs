@@ -1,5 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint, Burn};
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, TransferChecked, Mint};
+use anchor_spl::token::{self, Token};
+use anchor_spl::token::spl_token::native_mint;
+use apeout_swap::SwapPool;
+use apeout_swap::math;
+use apeout_swap::program::ApeoutSwap;
+use project_status_tracker::{ProjectTracker, TokenStatus};
+use protocol_config::ProtocolConfig;
 
 declare_id!("423GhdzXo7gogEHQz5Np2FfmF28P3B3acHufnU8WcHey");
 
@@ -18,6 +25,8 @@ pub mod lp_custody {
         vault.lp_available_for_claims = 0;
         vault.total_claimed = 0;
         vault.swap_pool = Pubkey::default(); // Will be set when swap pool is created
+        vault.sol_for_claims = 0;
+        vault.tokens_for_claims = 0;
         
         msg!("LP vault initialized for token {}", vault.token_mint);
         Ok(())
@@ -49,17 +58,19 @@ pub mod lp_custody {
             &[ctx.accounts.swap_pool.bump],
         ];
 
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.source_lp_account.to_account_info(),
+                    mint: ctx.accounts.lp_mint.to_account_info(),
                     to: ctx.accounts.vault_lp_account.to_account_info(),
                     authority: ctx.accounts.swap_pool.to_account_info(),
                 },
                 &[seeds],
             ),
             lp_amount,
+            ctx.accounts.lp_mint.decimals,
         )?;
 
         vault.total_lp += lp_amount;
@@ -75,16 +86,18 @@ pub mod lp_custody {
         require!(vault.is_active, ErrorCode::TokenIsDead);
         require!(amount > 0, ErrorCode::InvalidAmount);
 
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.from_lp_account.to_account_info(),
+                    mint: ctx.accounts.lp_mint.to_account_info(),
                     to: ctx.accounts.vault_lp_account.to_account_info(),
                     authority: ctx.accounts.from.to_account_info(),
                 },
             ),
             amount,
+            ctx.accounts.lp_mint.decimals,
         )?;
 
         vault.total_lp += amount;
@@ -115,83 +128,130 @@ pub mod lp_custody {
         // Get total LP tokens in vault
         let total_lp = ctx.accounts.vault_lp_account.amount;
         
-        // Calculate splits: 80% to holders, 20% to platform
-        let platform_fee = total_lp * 20 / 100;
+        // Calculate splits: platform share from protocol config, rest to holders
+        let platform_fee_bps = ctx.accounts.protocol_config.lp_platform_fee_bps as u64;
+        let platform_fee = math::mul_div(total_lp, platform_fee_bps, math::BPS_DENOMINATOR)?;
         let holder_distribution = total_lp - platform_fee;
 
-        // Burn the LP tokens to release underlying assets from swap pool
         let vault_seeds = &[
             b"vault",
             token_mint.as_ref(),
             &[vault_bump],
         ];
+        let vault_seeds_slice: &[&[&[u8]]] = &[vault_seeds];
 
-        // First, burn LP tokens to get underlying assets back
-        token::burn(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Burn {
-                    mint: ctx.accounts.lp_mint.to_account_info(),
-                    from: ctx.accounts.vault_lp_account.to_account_info(),
-                    authority: ctx.accounts.lp_vault.to_account_info(),
-                },
-                &[vault_seeds],
-            ),
-            total_lp,
-        )?;
+        // First, burn LP tokens through the swap pool to get underlying assets back
+        let vault_sol_before = ctx.accounts.lp_vault.to_account_info().lamports();
+        let vault_tokens_before = ctx.accounts.vault_token_account.amount;
+
+        let cpi_accounts = apeout_swap::cpi::accounts::RemoveLiquidity {
+            swap_pool: ctx.accounts.swap_pool.to_account_info(),
+            token_mint: ctx.accounts.token_mint.to_account_info(),
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            pool_token_account: ctx.accounts.pool_token_account.to_account_info(),
+            pool_sol_account: ctx.accounts.pool_sol_account.to_account_info(),
+            pool_wsol_vault: ctx.accounts.pool_wsol_vault.as_ref().map(|a| a.to_account_info()),
+            user_wsol_account: ctx.accounts.vault_wsol_account.as_ref().map(|a| a.to_account_info()),
+            wsol_token_program: ctx.accounts.wsol_token_program.as_ref().map(|a| a.to_account_info()),
+            user_lp_account: ctx.accounts.vault_lp_account.to_account_info(),
+            user_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            lp_position: None,
+            user: ctx.accounts.lp_vault.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.apeout_swap_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_seeds_slice);
+
+        apeout_swap::cpi::remove_liquidity(cpi_ctx, total_lp, 0, 0, None)?;
 
         // Disable the swap pool
         let cpi_accounts = apeout_swap::cpi::accounts::DisablePool {
             swap_pool: ctx.accounts.swap_pool.to_account_info(),
+            protocol_config: ctx.accounts.protocol_config.to_account_info(),
             authority: ctx.accounts.lp_vault.to_account_info(),
         };
         let cpi_program = ctx.accounts.apeout_swap_program.to_account_info();
-        let vault_seeds_slice: &[&[&[u8]]] = &[vault_seeds];
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_seeds_slice);
         
         apeout_swap::cpi::disable_pool(cpi_ctx)?;
 
-        // After burning LP, the underlying SOL and tokens are returned to pool
-        // We need to extract them and distribute
+        // The released assets now sit in the vault; measure what actually arrived.
+        // Wrapped-SOL pools pay out WSOL, which is unwrapped into the vault by
+        // closing the account (its rent stays in the vault, uncounted).
+        let released_sol = match &mut ctx.accounts.vault_wsol_account {
+            Some(vault_wsol_account) => {
+                let wsol_token_program = ctx
+                    .accounts
+                    .wsol_token_program
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingWsolAccounts)?;
+
+                vault_wsol_account.reload()?;
+                let released_wsol = vault_wsol_account.amount;
+
+                token::close_account(CpiContext::new_with_signer(
+                    wsol_token_program.to_account_info(),
+                    token::CloseAccount {
+                        account: vault_wsol_account.to_account_info(),
+                        destination: ctx.accounts.lp_vault.to_account_info(),
+                        authority: ctx.accounts.lp_vault.to_account_info(),
+                    },
+                    vault_seeds_slice,
+                ))?;
 
-        // Transfer platform fee (20% of underlying SOL) to platform
-        let pool_sol_balance = ctx.accounts.pool_sol_account.lamports();
-        let platform_sol_fee = pool_sol_balance * 20 / 100;
+                released_wsol
+            }
+            None => ctx.accounts.lp_vault.to_account_info().lamports() - vault_sol_before,
+        };
+        ctx.accounts.vault_token_account.reload()?;
+        let released_tokens = ctx.accounts.vault_token_account.amount - vault_tokens_before;
+
+        // Transfer platform fee (share of underlying SOL) to platform
+        let platform_sol_fee = math::mul_div(released_sol, platform_fee_bps, math::BPS_DENOMINATOR)?;
         
-        **ctx.accounts.pool_sol_account.to_account_info().try_borrow_mut_lamports()? -= platform_sol_fee;
+        **ctx.accounts.lp_vault.to_account_info().try_borrow_mut_lamports()? -= platform_sol_fee;
         **ctx.accounts.platform_treasury.to_account_info().try_borrow_mut_lamports()? += platform_sol_fee;
 
-        // Transfer platform fee (20% of underlying tokens) to platform
-        let pool_token_balance = ctx.accounts.pool_token_account.amount;
-        let platform_token_fee = pool_token_balance * 20 / 100;
+        // Transfer platform fee (share of underlying tokens) to platform
+        let platform_token_fee = math::mul_div(released_tokens, platform_fee_bps, math::BPS_DENOMINATOR)?;
 
-        let swap_pool_seeds = &[
-            b"swap_pool",
-            token_mint.as_ref(),
-            &[ctx.accounts.swap_pool.bump],
-        ];
-
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.pool_token_account.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
                     to: ctx.accounts.platform_token_account.to_account_info(),
-                    authority: ctx.accounts.swap_pool.to_account_info(),
+                    authority: ctx.accounts.lp_vault.to_account_info(),
                 },
-                &[swap_pool_seeds],
+                vault_seeds_slice,
             ),
             platform_token_fee,
+            ctx.accounts.token_mint.decimals,
         )?;
 
         // Update vault state AFTER all operations
         let vault = &mut ctx.accounts.lp_vault;
         vault.lp_available_for_claims = holder_distribution;
+        vault.sol_for_claims = released_sol - platform_sol_fee;
+        vault.tokens_for_claims = released_tokens - platform_token_fee;
         vault.is_active = false; // No more deposits allowed
 
+        emit!(LPDistributionPrepared {
+            lp_vault: vault.key(),
+            token_mint,
+            lp_burned: total_lp,
+            platform_sol_fee,
+            platform_token_fee,
+            sol_for_claims: vault.sol_for_claims,
+            tokens_for_claims: vault.tokens_for_claims,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         msg!("LP distribution prepared: {} LP burned, assets distributed", total_lp);
         msg!("Platform fee: {} SOL + {} tokens", platform_sol_fee, platform_token_fee);
-        msg!("Available for holder claims: {} (value equivalent)", holder_distribution);
+        msg!("Available for holder claims: {} SOL + {} tokens", vault.sol_for_claims, vault.tokens_for_claims);
 
         Ok(())
     }
@@ -202,51 +262,61 @@ pub mod lp_custody {
         holder_token_balance_at_death: u64,
         total_supply_at_death: u64
     ) -> Result<()> {
-        let vault = &ctx.accounts.lp_vault;
+        // Extract values before borrowing vault mutably
+        let vault_bump;
+        let token_mint;
+        let sol_for_claims;
+        let tokens_for_claims;
+
+        {
+            let vault = &ctx.accounts.lp_vault;
+            require!(vault.lp_available_for_claims > 0, ErrorCode::NoLPAvailable);
+            vault_bump = vault.bump;
+            token_mint = vault.token_mint;
+            sol_for_claims = vault.sol_for_claims;
+            tokens_for_claims = vault.tokens_for_claims;
+        }
+
         let claim_record = &mut ctx.accounts.claim_record;
         
         require!(!claim_record.claimed, ErrorCode::AlreadyClaimed);
-        require!(vault.lp_available_for_claims > 0, ErrorCode::NoLPAvailable);
         require!(holder_token_balance_at_death > 0, ErrorCode::NoTokensAtDeath);
 
-        // Calculate holder's proportional share of remaining assets
-        let holder_share_percentage = (holder_token_balance_at_death as u128 * 100) / total_supply_at_death as u128;
-        
-        // Get current balances in the pool (after platform fee was taken)
-        let pool_sol_balance = ctx.accounts.pool_sol_account.lamports();
-        let pool_token_balance = ctx.accounts.pool_token_account.amount;
-
-        // Calculate holder's share of SOL and tokens
-        let holder_sol_share = (pool_sol_balance as u128 * holder_share_percentage / 100) as u64;
-        let holder_token_share = (pool_token_balance as u128 * holder_share_percentage / 100) as u64;
+        // Calculate holder's proportional share of the assets released at death
+        let holder_sol_share = (sol_for_claims as u128 * holder_token_balance_at_death as u128
+            / total_supply_at_death as u128) as u64;
+        let holder_token_share = (tokens_for_claims as u128 * holder_token_balance_at_death as u128
+            / total_supply_at_death as u128) as u64;
 
         require!(holder_sol_share > 0 || holder_token_share > 0, ErrorCode::ShareTooSmall);
 
         // Transfer SOL to holder
         if holder_sol_share > 0 {
-            **ctx.accounts.pool_sol_account.to_account_info().try_borrow_mut_lamports()? -= holder_sol_share;
+            **ctx.accounts.lp_vault.to_account_info().try_borrow_mut_lamports()? -= holder_sol_share;
             **ctx.accounts.holder.to_account_info().try_borrow_mut_lamports()? += holder_sol_share;
         }
 
         // Transfer tokens to holder
         if holder_token_share > 0 {
-            let swap_pool_seeds = &[
-                b"swap_pool",
-                vault.token_mint.as_ref(),
-                &[ctx.accounts.swap_pool.bump],
+            let vault_seeds = &[
+                b"vault",
+                token_mint.as_ref(),
+                &[vault_bump],
             ];
 
-            token::transfer(
+            token_interface::transfer_checked(
                 CpiContext::new_with_signer(
                     ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.pool_token_account.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vault_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
                         to: ctx.accounts.holder_token_account.to_account_info(),
-                        authority: ctx.accounts.swap_pool.to_account_info(),
+                        authority: ctx.accounts.lp_vault.to_account_info(),
                     },
-                    &[swap_pool_seeds],
+                    &[vault_seeds],
                 ),
                 holder_token_share,
+                ctx.accounts.token_mint.decimals,
             )?;
         }
 
@@ -260,6 +330,15 @@ pub mod lp_custody {
         let vault = &mut ctx.accounts.lp_vault;
         vault.total_claimed += 1; // Count of claims, not amount
 
+        emit!(HolderClaimed {
+            lp_vault: vault.key(),
+            token_mint,
+            holder: ctx.accounts.holder.key(),
+            sol_amount: holder_sol_share,
+            token_amount: holder_token_share,
+            timestamp: claim_record.claim_time,
+        });
+
         msg!("Holder {} claimed {} SOL + {} tokens", 
              ctx.accounts.holder.key(), holder_sol_share, holder_token_share);
 
@@ -288,10 +367,10 @@ pub struct InitializeLPVault<'info> {
         payer = payer, 
         seeds = [b"vault", token_mint.key().as_ref()], 
         bump, 
-        space = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 32 + 1
+        space = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 32 + 1 + 8 + 8
     )]
     pub lp_vault: Account<'info, LPVault>,
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -305,90 +384,126 @@ pub struct DepositLPFromSwap<'info> {
         bump = lp_vault.bump
     )]
     pub lp_vault: Account<'info, LPVault>,
-    pub token_mint: Account<'info, Mint>,
-    pub lp_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
     
     #[account(mut)]
-    pub source_lp_account: Account<'info, TokenAccount>,
+    pub source_lp_account: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
         token::mint = lp_mint,
         token::authority = lp_vault,
     )]
-    pub vault_lp_account: Account<'info, TokenAccount>,
+    pub vault_lp_account: InterfaceAccount<'info, TokenAccount>,
     
-    #[account(seeds = [b"swap_pool", token_mint.key().as_ref()], bump)]
+    #[account(seeds = [b"swap_pool", token_mint.key().as_ref()], bump = swap_pool.bump, seeds::program = apeout_swap::ID)]
     pub swap_pool: Account<'info, SwapPool>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct DepositLP<'info> {
     #[account(mut, seeds = [b"vault", token_mint.key().as_ref()], bump = lp_vault.bump)]
     pub lp_vault: Account<'info, LPVault>,
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = lp_vault.lp_mint)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub from: Signer<'info>,
     #[account(mut)]
-    pub from_lp_account: Account<'info, TokenAccount>,
+    pub from_lp_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub vault_lp_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub vault_lp_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct PrepareLPDistribution<'info> {
     #[account(mut, seeds = [b"vault", token_mint.key().as_ref()], bump = lp_vault.bump)]
     pub lp_vault: Account<'info, LPVault>,
-    pub token_mint: Account<'info, Mint>,
-    pub lp_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
     
     #[account(mut)]
-    pub vault_lp_account: Account<'info, TokenAccount>,
+    pub vault_lp_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = lp_vault,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
     
     #[account(mut)]
-    pub pool_token_account: Account<'info, TokenAccount>,
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
     
     /// CHECK: Pool SOL account
     #[account(mut)]
     pub pool_sol_account: AccountInfo<'info>,
     
+    /// CHECK: Pool WSOL vault, validated by apeout_swap (wrapped-SOL pools only)
     #[account(mut)]
-    pub platform_token_account: Account<'info, TokenAccount>,
+    pub pool_wsol_vault: Option<AccountInfo<'info>>,
     
-    /// CHECK: Platform treasury SOL account
-    #[account(mut)]
+    /// Vault-owned WSOL account the pool pays into; closed to unwrap (wrapped-SOL pools only)
+    #[account(
+        mut,
+        token::mint = native_mint::ID,
+        token::authority = lp_vault,
+    )]
+    pub vault_wsol_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    pub wsol_token_program: Option<Program<'info, Token>>,
+    
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = protocol_config.platform_treasury,
+    )]
+    pub platform_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Platform treasury SOL account from the protocol config
+    #[account(mut, address = protocol_config.platform_treasury)]
     pub platform_treasury: AccountInfo<'info>,
     
-    #[account(mut, seeds = [b"swap_pool", token_mint.key().as_ref()], bump)]
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"swap_pool", token_mint.key().as_ref()],
+        bump = swap_pool.bump,
+        seeds::program = apeout_swap::ID
+    )]
     pub swap_pool: Account<'info, SwapPool>,
     
-    #[account(seeds = [b"tracker", token_mint.key().as_ref()], bump)]
+    #[account(seeds = [b"tracker", token_mint.key().as_ref()], bump, seeds::program = project_status_tracker::ID)]
     pub tracker: Account<'info, ProjectTracker>,
     
-    /// CHECK: ApeOut swap program
-    pub apeout_swap_program: AccountInfo<'info>,
+    pub apeout_swap_program: Program<'info, ApeoutSwap>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct ClaimHolderLP<'info> {
-    #[account(seeds = [b"vault", token_mint.key().as_ref()], bump = lp_vault.bump)]
+    #[account(mut, seeds = [b"vault", token_mint.key().as_ref()], bump = lp_vault.bump)]
     pub lp_vault: Account<'info, LPVault>,
-    pub token_mint: Account<'info, Mint>,
-    
-    #[account(mut)]
-    pub pool_token_account: Account<'info, TokenAccount>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     
-    /// CHECK: Pool SOL account
-    #[account(mut)]
-    pub pool_sol_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = lp_vault,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
     
     #[account(mut)]
-    pub holder_token_account: Account<'info, TokenAccount>,
+    pub holder_token_account: InterfaceAccount<'info, TokenAccount>,
     
     #[account(mut)]
     pub holder: Signer<'info>,
@@ -402,18 +517,15 @@ pub struct ClaimHolderLP<'info> {
     )]
     pub claim_record: Account<'info, ClaimRecord>,
     
-    #[account(seeds = [b"swap_pool", token_mint.key().as_ref()], bump)]
-    pub swap_pool: Account<'info, SwapPool>,
-    
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct ValidateSwap<'info> {
-    #[account(seeds = [b"tracker", token_mint.key().as_ref()], bump)]
+    #[account(seeds = [b"tracker", token_mint.key().as_ref()], bump, seeds::program = project_status_tracker::ID)]
     pub tracker: Account<'info, ProjectTracker>,
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 }
 
 // ===== DATA STRUCTURES =====
@@ -428,6 +540,8 @@ pub struct LPVault {
     pub lp_available_for_claims: u64,  // LP equivalent available for claims
     pub total_claimed: u64,            // Number of successful claims
     pub swap_pool: Pubkey,             // Associated swap pool
+    pub sol_for_claims: u64,           // SOL released at death for holder claims
+    pub tokens_for_claims: u64,        // Tokens released at death for holder claims
 }
 
 #[account]
@@ -438,37 +552,28 @@ pub struct ClaimRecord {
     pub claim_time: i64,               // When claim was made
 }
 
-// External account structures (imported from other programs)
-#[account]
-pub struct ProjectTracker {
-    pub token_mint: Pubkey,
-    pub launch_time: i64,
-    pub last_trade_ts: i64,
-    pub volume_3d: u64,
-    pub ath_price: u64,
-    pub current_price: u64,
-    pub status: TokenStatus,
-    pub death_snapshot_time: i64,
-}
+// ===== EVENTS =====
 
-#[account]
-pub struct SwapPool {
+#[event]
+pub struct LPDistributionPrepared {
+    pub lp_vault: Pubkey,
     pub token_mint: Pubkey,
-    pub token_reserve: u64,
-    pub sol_reserve: u64,
-    pub lp_mint: Pubkey,
-    pub total_lp_supply: u64,
-    pub fee_rate: u16,
-    pub is_active: bool,
-    pub created_at: i64,
-    pub bump: u8,
+    pub lp_burned: u64,                // LP tokens redeemed from the pool
+    pub platform_sol_fee: u64,
+    pub platform_token_fee: u64,
+    pub sol_for_claims: u64,           // SOL left for holder claims
+    pub tokens_for_claims: u64,        // Tokens left for holder claims
+    pub timestamp: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum TokenStatus {
-    Active,
-    Warning,
-    Dead,
+#[event]
+pub struct HolderClaimed {
+    pub lp_vault: Pubkey,
+    pub token_mint: Pubkey,
+    pub holder: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
 }
 
 #[error_code]
@@ -495,4 +600,6 @@ pub enum ErrorCode {
     InvalidAmount,
     #[msg("LP mint does not match vault configuration.")]
     InvalidLPMint,
+    #[msg("Wrapped-SOL accounts are missing.")]
+    MissingWsolAccounts,
 }
\ No newline at end of file
@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
+use protocol_config::ProtocolConfig;
 
 declare_id!("GRibyZMmqCVuFiXkbVfNQcwvmu6mqhqxhwEHVazaJGj8");
 
+/// Swap program whose pools are the only source of trade stats
+pub mod apeout_swap_program {
+    use super::*;
+    declare_id!("SwApCoNtRaCt1111111111111111111111111111111");
+}
+
 #[program]
 pub mod project_status_tracker {
     use super::*;
@@ -19,6 +26,7 @@ pub mod project_status_tracker {
         Ok(())
     }
 
+    /// Record a trade; only callable by the token's swap pool via CPI
     pub fn update_stats(
         ctx: Context<UpdateStats>,
         volume_delta: u64,
@@ -26,7 +34,10 @@ pub mod project_status_tracker {
     ) -> Result<()> {
         let tracker = &mut ctx.accounts.tracker;
         
-        require!(tracker.status != TokenStatus::Dead, ErrorCode::TokenIsDead);
+        // Sells of dead tokens still go through the swap; their stats are frozen
+        if tracker.status == TokenStatus::Dead {
+            return Ok(());
+        }
         
         tracker.volume_3d += volume_delta;
         tracker.current_price = current_price;
@@ -47,9 +58,11 @@ pub mod project_status_tracker {
             return Ok(());
         }
 
+        let previous_status = tracker.status.clone();
+        let config = &ctx.accounts.protocol_config;
         let age_in_seconds = now - tracker.launch_time;
-        let min_age = 259_200; // 3 days in seconds
-        let vol_threshold = 15_000_000_000; // 15 SOL in lamports
+        let min_age = config.dead_min_age;
+        let vol_threshold = config.dead_volume_threshold;
 
         if age_in_seconds >= min_age && tracker.volume_3d < vol_threshold {
             tracker.status = TokenStatus::Dead;
@@ -61,10 +74,18 @@ pub mod project_status_tracker {
                 tracker.volume_3d / 1_000_000_000
             );
         } 
-        else if age_in_seconds >= (min_age - 86400) {
-            if tracker.volume_3d < (vol_threshold * 2) {
-                tracker.status = TokenStatus::Warning;
-            }
+        else if age_in_seconds >= (min_age - config.warning_window) && tracker.volume_3d < (vol_threshold * 2) {
+            tracker.status = TokenStatus::Warning;
+        }
+
+        if tracker.status != previous_status {
+            emit!(StatusChanged {
+                token_mint: tracker.token_mint,
+                previous_status,
+                new_status: tracker.status.clone(),
+                volume_3d: tracker.volume_3d,
+                timestamp: now,
+            });
         }
 
         Ok(())
@@ -101,6 +122,13 @@ pub struct UpdateStats<'info> {
     pub tracker: Account<'info, ProjectTracker>,
     /// CHECK: This is just a reference to the token mint
     pub token_mint: AccountInfo<'info>,
+    /// The token's swap pool, signing through apeout_swap
+    #[account(
+        seeds = [b"swap_pool", token_mint.key().as_ref()],
+        bump,
+        seeds::program = apeout_swap_program::ID
+    )]
+    pub swap_pool: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -109,6 +137,8 @@ pub struct CheckAndFlagDead<'info> {
     pub tracker: Account<'info, ProjectTracker>,
     /// CHECK: This is just a reference to the token mint
     pub token_mint: AccountInfo<'info>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump, seeds::program = protocol_config::ID)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
 #[derive(Accounts)]
@@ -138,6 +168,15 @@ pub enum TokenStatus {
     Dead,
 }
 
+#[event]
+pub struct StatusChanged {
+    pub token_mint: Pubkey,
+    pub previous_status: TokenStatus,
+    pub new_status: TokenStatus,
+    pub volume_3d: u64,
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Token is dead - no more stat updates allowed.")]
@@ -0,0 +1,303 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::system_program;
+
+declare_id!("ConFig1111111111111111111111111111111111111");
+
+// Highest fee a pool can start its launch window with (50%)
+pub const MAX_LAUNCH_FEE_BPS: u16 = 5000;
+
+// Highest dynamic swap fee governance can allow (50%)
+pub const MAX_SWAP_FEE_BPS: u16 = 5000;
+
+#[program]
+pub mod protocol_config {
+    use super::*;
+
+    /// Create the global config; only the program's upgrade authority can do this
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        params.validate()?;
+
+        let config = &mut ctx.accounts.protocol_config;
+        config.admin = ctx.accounts.admin.key();
+        config.pending_admin = None;
+        config.bump = *ctx.bumps.get("protocol_config").unwrap();
+        config.apply(&params);
+
+        msg!("Protocol config initialized, admin: {}", config.admin);
+        Ok(())
+    }
+
+    /// Update treasury, fee splits and thresholds (admin only)
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        params.validate()?;
+
+        let config = &mut ctx.accounts.protocol_config;
+        config.apply(&params);
+
+        msg!("Protocol config updated by {}", ctx.accounts.admin.key());
+        Ok(())
+    }
+
+    /// Step 1 of an admin transfer: nominate the new admin (admin only)
+    pub fn propose_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        config.pending_admin = Some(new_admin);
+
+        msg!("Admin transfer proposed: {} -> {}", config.admin, new_admin);
+        Ok(())
+    }
+
+    /// Drop a pending admin transfer (admin only)
+    pub fn cancel_admin_transfer(ctx: Context<UpdateConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        require!(config.pending_admin.is_some(), ConfigError::NoPendingAdmin);
+        config.pending_admin = None;
+
+        msg!("Admin transfer cancelled");
+        Ok(())
+    }
+
+    /// Step 2 of an admin transfer: the nominated admin accepts
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        let previous_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        msg!("Admin transferred: {} -> {}", previous_admin, config.admin);
+        Ok(())
+    }
+
+    /// Grow a config created with an older layout to ProtocolConfig::LEN and set
+    /// every parameter, including the appended ones (admin only; the admin funds
+    /// the extra rent)
+    pub fn migrate_config(ctx: Context<MigrateConfig>, params: ConfigParams) -> Result<()> {
+        params.validate()?;
+
+        let config_info = ctx.accounts.protocol_config.to_account_info();
+        let old_len = config_info.data_len();
+        require!(old_len < ProtocolConfig::LEN, ConfigError::ConfigUpToDate);
+
+        // Only the config PDA of this program can be grown, and only by its admin
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(
+                config_info.owner == &crate::ID && old_len >= 8 + 32 && data[..8] == ProtocolConfig::DISCRIMINATOR,
+                ConfigError::InvalidConfigAccount
+            );
+            let (expected, _) = Pubkey::find_program_address(&[b"protocol_config"], &crate::ID);
+            require!(config_info.key() == expected, ConfigError::InvalidConfigAccount);
+            require!(data[8..40] == ctx.accounts.admin.key().to_bytes(), ConfigError::Unauthorized);
+        }
+
+        let rent_due = Rent::get()?.minimum_balance(ProtocolConfig::LEN).saturating_sub(config_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: config_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        config_info.realloc(ProtocolConfig::LEN, true)?;
+
+        let mut config = Account::<ProtocolConfig>::try_from(&config_info)?;
+        config.apply(&params);
+        config.exit(&crate::ID)?;
+
+        msg!("Protocol config migrated: {} -> {} bytes", old_len, ProtocolConfig::LEN);
+        Ok(())
+    }
+}
+
+// ===== ACCOUNT CONTEXTS =====
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"protocol_config"],
+        bump,
+        space = ProtocolConfig::LEN
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::ProtocolConfig>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ConfigError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        has_one = admin @ ConfigError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"protocol_config"],
+        bump = protocol_config.bump,
+        constraint = protocol_config.pending_admin == Some(new_admin.key()) @ ConfigError::NotPendingAdmin
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: Config in an older layout, checked and deserialized by the handler
+    #[account(mut)]
+    pub protocol_config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ===== DATA STRUCTURES =====
+
+#[account]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,                 // Authority for all privileged instructions
+    pub pending_admin: Option<Pubkey>, // Nominated admin awaiting acceptance
+    pub platform_treasury: Pubkey,     // Receives platform fees (SOL and tokens)
+    pub holder_fee_bps: u16,           // Share of swap fees for holders
+    pub game_fee_bps: u16,             // Share of swap fees for the daily game (platform gets the rest)
+    pub lp_platform_fee_bps: u16,      // Platform share of LP released from dead tokens
+    pub dead_min_age: i64,             // Minimum token age before it can be declared dead
+    pub dead_volume_threshold: u64,    // Volume below which an old token is dead
+    pub warning_window: i64,           // How long before dead_min_age a token can be flagged
+    pub bump: u8,                      // PDA bump
+    pub launch_window: i64,            // Anti-sniper window for new pools in seconds (0 = off)
+    pub launch_max_buy_per_slot: u64,  // Max SOL a wallet can buy per slot in the window (0 = no limit)
+    pub launch_max_buy_per_wallet: u64, // Max SOL a wallet can buy over the window (0 = no limit)
+    pub launch_fee_bps: u16,           // Pool fee at creation, decays to the pool fee over the window
+    pub referral_fee_bps: u16,         // Share of swap fees paid to the trade's referrer
+    pub min_fee_bps: u16,              // Lower bound of the dynamic swap fee
+    pub max_fee_bps: u16,              // Upper bound of the dynamic swap fee
+    pub young_pool_age: i64,           // Seconds over which the young pool fee premium decays (0 = off)
+    pub young_pool_fee_bps: u16,       // Fee premium at pool creation
+    pub volatility_fee_bps: u16,       // Share of realized volatility (bps) added to the fee
+    pub lp_fee_bps: u16,               // Share of swap fees left in the pool for liquidity providers
+}
+
+impl ProtocolConfig {
+    /// Account size including the discriminator. Fields are only ever appended,
+    /// so migrate_config can grow configs created with an older layout.
+    pub const LEN: usize = 8 + 32 + 33 + 32 + 2 + 2 + 2 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 2 + 2 + 2 + 2 + 8 + 2 + 2 + 2;
+
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        self.admin == *key
+    }
+
+    fn apply(&mut self, params: &ConfigParams) {
+        self.platform_treasury = params.platform_treasury;
+        self.holder_fee_bps = params.holder_fee_bps;
+        self.game_fee_bps = params.game_fee_bps;
+        self.lp_platform_fee_bps = params.lp_platform_fee_bps;
+        self.dead_min_age = params.dead_min_age;
+        self.dead_volume_threshold = params.dead_volume_threshold;
+        self.warning_window = params.warning_window;
+        self.launch_window = params.launch_window;
+        self.launch_max_buy_per_slot = params.launch_max_buy_per_slot;
+        self.launch_max_buy_per_wallet = params.launch_max_buy_per_wallet;
+        self.launch_fee_bps = params.launch_fee_bps;
+        self.referral_fee_bps = params.referral_fee_bps;
+        self.min_fee_bps = params.min_fee_bps;
+        self.max_fee_bps = params.max_fee_bps;
+        self.young_pool_age = params.young_pool_age;
+        self.young_pool_fee_bps = params.young_pool_fee_bps;
+        self.volatility_fee_bps = params.volatility_fee_bps;
+        self.lp_fee_bps = params.lp_fee_bps;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub platform_treasury: Pubkey,
+    pub holder_fee_bps: u16,
+    pub game_fee_bps: u16,
+    pub lp_platform_fee_bps: u16,
+    pub dead_min_age: i64,
+    pub dead_volume_threshold: u64,
+    pub warning_window: i64,
+    pub launch_window: i64,
+    pub launch_max_buy_per_slot: u64,
+    pub launch_max_buy_per_wallet: u64,
+    pub launch_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub min_fee_bps: u16,
+    pub max_fee_bps: u16,
+    pub young_pool_age: i64,
+    pub young_pool_fee_bps: u16,
+    pub volatility_fee_bps: u16,
+    pub lp_fee_bps: u16,
+}
+
+impl ConfigParams {
+    fn validate(&self) -> Result<()> {
+        require!(
+            self.holder_fee_bps as u32 + self.game_fee_bps as u32 <= 10000,
+            ConfigError::InvalidFeeSplit
+        );
+        require!(self.lp_platform_fee_bps <= 10000, ConfigError::InvalidFeeSplit);
+        require!(
+            self.referral_fee_bps as u32 + self.lp_fee_bps as u32 <= 10000,
+            ConfigError::InvalidFeeSplit
+        );
+        require!(
+            self.dead_min_age > 0 && self.warning_window >= 0 && self.warning_window <= self.dead_min_age,
+            ConfigError::InvalidThreshold
+        );
+        require!(
+            self.launch_window >= 0 && self.launch_fee_bps <= MAX_LAUNCH_FEE_BPS,
+            ConfigError::InvalidLaunchProtection
+        );
+        require!(
+            self.min_fee_bps <= self.max_fee_bps && self.max_fee_bps <= MAX_SWAP_FEE_BPS && self.young_pool_age >= 0,
+            ConfigError::InvalidFeeBounds
+        );
+        Ok(())
+    }
+}
+
+#[error_code]
+pub enum ConfigError {
+    #[msg("Signer is not the protocol admin.")]
+    Unauthorized,
+    #[msg("Signer is not the pending admin.")]
+    NotPendingAdmin,
+    #[msg("No admin transfer is pending.")]
+    NoPendingAdmin,
+    #[msg("Fee split exceeds 100%.")]
+    InvalidFeeSplit,
+    #[msg("Invalid death threshold configuration.")]
+    InvalidThreshold,
+    #[msg("Invalid launch protection configuration.")]
+    InvalidLaunchProtection,
+    #[msg("Invalid dynamic fee configuration.")]
+    InvalidFeeBounds,
+    #[msg("Config already has the current layout.")]
+    ConfigUpToDate,
+    #[msg("Account is not the protocol config.")]
+    InvalidConfigAccount,
+}
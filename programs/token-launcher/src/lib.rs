@@ -1,12 +1,130 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint, MintTo};
+use anchor_lang::system_program;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, TransferChecked, Mint, MintTo, Burn, InitializeMint2};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::extension::metadata_pointer;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
 
 declare_id!("ToKeNLaUnChEr111111111111111111111111111111");
 
+// Curve prices are lamports per token base unit, scaled by 1e9 (same as apeout_swap::get_price)
+pub const PRICE_SCALE: u128 = 1_000_000_000;
+// Upper bound on price steps of an exponential curve, keeps buys within compute limits
+pub const MAX_CURVE_STEPS: u64 = 200;
+
 #[program]
 pub mod token_launcher {
     use super::*;
 
+    /// Create the mint for a launch (SPL Token or Token-2022). Token-2022 mints carry
+    /// their name, symbol and uri in a TokenMetadata extension on the mint itself,
+    /// with launch_data as update authority.
+    pub fn create_launch_mint(
+        ctx: Context<CreateLaunchMint>,
+        name: String,
+        symbol: String,
+        uri: String
+    ) -> Result<()> {
+        require!(name.len() <= 32, LaunchError::NameTooLong);
+        require!(symbol.len() <= 10, LaunchError::SymbolTooLong);
+        require!(uri.len() <= 200, LaunchError::UriTooLong);
+
+        let token_program = ctx.accounts.token_program.key();
+        let token_mint = ctx.accounts.token_mint.key();
+        let launch_data = ctx.accounts.launch_data.key();
+        let is_token_2022 = token_program == spl_token_2022::ID;
+
+        // The metadata extension is written after the mint is initialized and grows
+        // the account itself, so only its rent is paid up front
+        let (space, metadata_space) = if is_token_2022 {
+            let metadata = TokenMetadata {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                ..Default::default()
+            };
+            (
+                ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                    ExtensionType::MetadataPointer,
+                ])?,
+                metadata.tlv_size_of()?,
+            )
+        } else {
+            (spl_token_2022::state::Mint::LEN, 0)
+        };
+
+        // Step 1: Allocate the mint account owned by the chosen token program
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            Rent::get()?.minimum_balance(space + metadata_space),
+            space as u64,
+            &token_program,
+        )?;
+
+        // Step 2: Extensions must be initialized before the mint itself; the
+        // metadata pointer points at the mint
+        if is_token_2022 {
+            let ix = metadata_pointer::instruction::initialize(
+                &token_program,
+                &token_mint,
+                Some(launch_data),
+                Some(token_mint),
+            )?;
+            invoke(&ix, &[ctx.accounts.token_mint.to_account_info()])?;
+        }
+
+        // Step 3: Initialize the mint with launch_data as mint authority
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint2 {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            6,
+            &launch_data,
+            None,
+        )?;
+
+        // Step 4: Write the metadata into the mint, signed by launch_data as mint authority
+        if is_token_2022 {
+            let ix = spl_token_metadata_interface::instruction::initialize(
+                &token_program,
+                &token_mint,
+                &launch_data,
+                &token_mint,
+                &launch_data,
+                name,
+                symbol,
+                uri,
+            );
+            let seeds = &[
+                b"launch_data",
+                token_mint.as_ref(),
+                &[*ctx.bumps.get("launch_data").unwrap()],
+            ];
+            invoke_signed(
+                &ix,
+                &[
+                    ctx.accounts.token_mint.to_account_info(),
+                    ctx.accounts.launch_data.to_account_info(),
+                ],
+                &[seeds],
+            )?;
+        }
+
+        msg!("Launch mint created: {} (Token-2022: {})", ctx.accounts.token_mint.key(), is_token_2022);
+        Ok(())
+    }
+
     /// Launch a new token with immediate LP creation and custody lock
     pub fn launch_token(
         ctx: Context<LaunchToken>,
@@ -23,6 +141,7 @@ pub mod token_launcher {
         require!(initial_supply > 0, LaunchError::InvalidSupply);
         require!(lp_sol_amount >= 10_000_000, LaunchError::InsufficientLPFunding); // Min 0.01 SOL
         require!(lp_token_amount > 0, LaunchError::InvalidLPTokenAmount);
+        check_mint_metadata(&ctx.accounts.token_mint.to_account_info(), &name, &symbol, &uri)?;
 
         let launch_data = &mut ctx.accounts.launch_data;
         let clock = Clock::get()?;
@@ -47,7 +166,7 @@ let seeds = &[
     &[launch_data.bump],
 ];
 
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
@@ -85,16 +204,18 @@ let seeds = &[
         lp_custody::cpi::initialize_lp_vault(cpi_ctx)?;
 
         // Step 4: Transfer LP tokens from creator to launch contract temporarily
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.creator_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
                     to: ctx.accounts.launch_token_temp_account.to_account_info(),
                     authority: ctx.accounts.creator.to_account_info(),
                 },
             ),
             lp_token_amount,
+            ctx.accounts.token_mint.decimals,
         )?;
 
         // Step 5: Initialize swap pool with LP
@@ -107,7 +228,9 @@ let seeds = &[
             pool_sol_account: ctx.accounts.pool_sol_account.to_account_info(),
             custody_lp_account: ctx.accounts.custody_lp_account.to_account_info(),
             custody_program: ctx.accounts.lp_custody_program.to_account_info(),
+            protocol_config: ctx.accounts.protocol_config.to_account_info(),
             creator: ctx.accounts.launch_data.to_account_info(),
+            payer: ctx.accounts.creator.to_account_info(),
             token_program: ctx.accounts.token_program.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
         };
@@ -125,6 +248,18 @@ let seeds = &[
         msg!("LP created: {} tokens + {} SOL", lp_token_amount, lp_sol_amount);
         msg!("LP tokens locked in custody contract");
 
+        emit!(TokenLaunched {
+            token_mint: ctx.accounts.token_mint.key(),
+            creator: ctx.accounts.creator.key(),
+            name,
+            symbol,
+            initial_supply,
+            lp_token_amount,
+            lp_sol_amount,
+            on_curve: false,
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
 
@@ -174,13 +309,532 @@ let seeds = &[
             launch_data.uri = uri;
         }
 
-        msg!("Token metadata updated for {}", launch_data.token_mint);
+        // Token-2022 mints carry the metadata too; keep it in step with launch_data
+        ctx.accounts.sync_mint_metadata()?;
+
+        msg!("Token metadata updated for {}", ctx.accounts.launch_data.token_mint);
+        Ok(())
+    }
+
+    /// Launch a new token on a bonding curve; the AMM pool is created at graduation
+    pub fn launch_token_with_curve(
+        ctx: Context<LaunchTokenWithCurve>,
+        name: String,
+        symbol: String,
+        uri: String,
+        initial_supply: u64,
+        creator_allocation: u64,
+        params: CurveParams,
+    ) -> Result<()> {
+        require!(name.len() <= 32, LaunchError::NameTooLong);
+        require!(symbol.len() <= 10, LaunchError::SymbolTooLong);
+        require!(uri.len() <= 200, LaunchError::UriTooLong);
+        require!(initial_supply > 0, LaunchError::InvalidSupply);
+        require!(creator_allocation <= initial_supply, LaunchError::InvalidSupply);
+        check_mint_metadata(&ctx.accounts.token_mint.to_account_info(), &name, &symbol, &uri)?;
+
+        // Everything not allocated to the creator goes to the curve reserve;
+        // the part not sold on the curve seeds the AMM pool at graduation. The
+        // curve never raises more than its final price times the tokens sold, so an
+        // LP reserve of at least the curve supply lets graduation open the pool at
+        // the curve's final price.
+        let reserve_amount = initial_supply - creator_allocation;
+        require!(
+            params.curve_supply > 0 && matches!(params.curve_supply.checked_mul(2), Some(n) if n <= reserve_amount),
+            LaunchError::InvalidCurveConfig
+        );
+        let lp_reserve = reserve_amount - params.curve_supply;
+        require!(
+            params.graduation_sol > 0 || params.graduation_market_cap > 0,
+            LaunchError::InvalidCurveConfig
+        );
+        validate_curve(&params.curve, params.start_price, params.curve_supply)?;
+
+        let clock = Clock::get()?;
+        let token_mint_key = ctx.accounts.token_mint.key();
+
+        // Store launch information
+        let launch_data = &mut ctx.accounts.launch_data;
+        launch_data.creator = ctx.accounts.creator.key();
+        launch_data.token_mint = token_mint_key;
+        launch_data.name = name.clone();
+        launch_data.symbol = symbol.clone();
+        launch_data.uri = uri;
+        launch_data.initial_supply = initial_supply;
+        launch_data.lp_sol_amount = 0; // Set at graduation
+        launch_data.lp_token_amount = 0; // Set at graduation
+        launch_data.launch_time = clock.unix_timestamp;
+        launch_data.bump = *ctx.bumps.get("launch_data").unwrap();
+
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.token_mint = token_mint_key;
+        curve.curve = params.curve;
+        curve.start_price = params.start_price;
+        curve.curve_supply = params.curve_supply;
+        curve.tokens_sold = 0;
+        curve.sol_raised = 0;
+        curve.graduation_sol = params.graduation_sol;
+        curve.graduation_market_cap = params.graduation_market_cap;
+        curve.is_complete = false;
+        curve.graduated = false;
+        curve.bump = *ctx.bumps.get("bonding_curve").unwrap();
+        curve.vault_bump = *ctx.bumps.get("curve_vault").unwrap();
+
+        let seeds = &[
+            b"launch_data",
+            token_mint_key.as_ref(),
+            &[launch_data.bump],
+        ];
+
+        // Mint the curve reserve and the creator allocation
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.curve_token_account.to_account_info(),
+                    authority: ctx.accounts.launch_data.to_account_info(),
+                },
+                &[seeds],
+            ),
+            reserve_amount,
+        )?;
+
+        if creator_allocation > 0 {
+            token_interface::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.creator_token_account.to_account_info(),
+                        authority: ctx.accounts.launch_data.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                creator_allocation,
+            )?;
+        }
+
+        // Fund the curve vault to rent exemption so small first buys can land
+        let vault_rent = Rent::get()?.minimum_balance(0);
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.curve_vault.to_account_info(),
+                },
+            ),
+            vault_rent,
+        )?;
+
+        msg!("Token launched on bonding curve: {} ({})", name, symbol);
+        msg!("Curve: {} tokens for sale, {} tokens reserved for LP",
+             params.curve_supply, lp_reserve);
+
+        emit!(TokenLaunched {
+            token_mint: token_mint_key,
+            creator: ctx.accounts.creator.key(),
+            name,
+            symbol,
+            initial_supply,
+            lp_token_amount: 0,
+            lp_sol_amount: 0,
+            on_curve: true,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Buy tokens from the bonding curve
+    pub fn buy_on_curve(
+        ctx: Context<TradeOnCurve>,
+        token_amount: u64,
+        max_sol_cost: u64
+    ) -> Result<()> {
+        require!(token_amount > 0, LaunchError::InvalidAmount);
+
+        let curve = &ctx.accounts.bonding_curve;
+        require!(!curve.is_complete, LaunchError::CurveComplete);
+
+        // The last buyer gets whatever is left on the curve
+        let amount = token_amount.min(curve.curve_supply - curve.tokens_sold);
+        let sol_cost = curve.cost_between(curve.tokens_sold, curve.tokens_sold + amount, true)?;
+
+        require!(sol_cost <= max_sol_cost, LaunchError::SlippageExceeded);
+
+        // Transfer SOL from buyer to curve vault
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.curve_vault.to_account_info(),
+                },
+            ),
+            sol_cost,
+        )?;
+
+        // Transfer tokens from curve reserve to buyer
+        let token_mint = curve.token_mint;
+        let vault_seeds = &[
+            b"curve_vault",
+            token_mint.as_ref(),
+            &[curve.vault_bump],
+        ];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.curve_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.curve_vault.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        // Update curve state AFTER all transfers
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.tokens_sold += amount;
+        curve.sol_raised += sol_cost;
+
+        let market_cap = curve.market_cap(ctx.accounts.token_mint.supply)?;
+        let sol_target_hit = curve.graduation_sol > 0 && curve.sol_raised >= curve.graduation_sol;
+        let market_cap_hit = curve.graduation_market_cap > 0 && market_cap >= curve.graduation_market_cap;
+
+        if sol_target_hit || market_cap_hit || curve.tokens_sold == curve.curve_supply {
+            curve.is_complete = true;
+            msg!("Bonding curve complete for {}: {} SOL raised, ready to graduate",
+                 curve.token_mint, curve.sol_raised);
+        }
+
+        msg!("Curve buy: {} tokens for {} SOL", amount, sol_cost);
         Ok(())
     }
+
+    /// Sell tokens back to the bonding curve (until it completes)
+    pub fn sell_on_curve(
+        ctx: Context<TradeOnCurve>,
+        token_amount: u64,
+        min_sol_out: u64
+    ) -> Result<()> {
+        require!(token_amount > 0, LaunchError::InvalidAmount);
+
+        let curve = &ctx.accounts.bonding_curve;
+        require!(!curve.is_complete, LaunchError::CurveComplete);
+        require!(token_amount <= curve.tokens_sold, LaunchError::InvalidAmount);
+
+        let sol_out = curve.cost_between(curve.tokens_sold - token_amount, curve.tokens_sold, false)?;
+
+        require!(sol_out >= min_sol_out, LaunchError::SlippageExceeded);
+
+        // Transfer tokens from seller back to curve reserve
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.curve_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            token_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        // Transfer SOL from curve vault to seller
+        let token_mint = curve.token_mint;
+        let vault_seeds = &[
+            b"curve_vault",
+            token_mint.as_ref(),
+            &[curve.vault_bump],
+        ];
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.curve_vault.to_account_info(),
+                    to: ctx.accounts.user.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            sol_out,
+        )?;
+
+        // Update curve state AFTER all transfers
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.tokens_sold -= token_amount;
+        curve.sol_raised -= sol_out;
+
+        msg!("Curve sell: {} tokens for {} SOL", token_amount, sol_out);
+        Ok(())
+    }
+
+    /// Migrate a completed bonding curve into an apeout_swap pool with LP locked in custody
+    pub fn graduate_curve(ctx: Context<GraduateCurve>) -> Result<()> {
+        let curve = &ctx.accounts.bonding_curve;
+        require!(curve.is_complete, LaunchError::CurveNotComplete);
+        require!(!curve.graduated, LaunchError::AlreadyGraduated);
+
+        let token_mint = curve.token_mint;
+        let vault_bump = curve.vault_bump;
+        let sol_amount = curve.sol_raised;
+
+        // Seed the pool at the curve's final price so graduation doesn't move the
+        // price; the LP reserve left over is burned
+        let final_price = curve.price_at(curve.tokens_sold)?;
+        let available = ctx.accounts.curve_token_account.amount;
+        let token_amount = (sol_amount as u128 * PRICE_SCALE / final_price).min(available as u128) as u64;
+        require!(token_amount > 0, LaunchError::InvalidLPTokenAmount);
+
+        // Step 1: Register with project status tracker (death clock starts at graduation)
+        let cpi_accounts = project_status_tracker::cpi::accounts::InitializeTracker {
+            tracker: ctx.accounts.project_tracker.to_account_info(),
+            token_mint: ctx.accounts.token_mint.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.project_status_tracker_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        project_status_tracker::cpi::initialize_tracker(cpi_ctx)?;
+
+        // Step 2: Initialize LP vault in custody
+        let cpi_accounts = lp_custody::cpi::accounts::InitializeLPVault {
+            lp_vault: ctx.accounts.lp_vault.to_account_info(),
+            token_mint: ctx.accounts.token_mint.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.lp_custody_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        lp_custody::cpi::initialize_lp_vault(cpi_ctx)?;
+
+        // Step 3: Seed the swap pool with the raised SOL and remaining tokens
+        let vault_seeds = &[
+            b"curve_vault",
+            token_mint.as_ref(),
+            &[vault_bump],
+        ];
+
+        let cpi_accounts = apeout_swap::cpi::accounts::InitSwapPool {
+            swap_pool: ctx.accounts.swap_pool.to_account_info(),
+            token_mint: ctx.accounts.token_mint.to_account_info(),
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            creator_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            pool_token_account: ctx.accounts.pool_token_account.to_account_info(),
+            pool_sol_account: ctx.accounts.pool_sol_account.to_account_info(),
+            custody_lp_account: ctx.accounts.custody_lp_account.to_account_info(),
+            custody_program: ctx.accounts.lp_custody_program.to_account_info(),
+            protocol_config: ctx.accounts.protocol_config.to_account_info(),
+            creator: ctx.accounts.curve_vault.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.apeout_swap_program.to_account_info();
+        let seeds_slice: &[&[&[u8]]] = &[vault_seeds];
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds_slice);
+        
+        apeout_swap::cpi::init_swap_pool(cpi_ctx, token_amount, sol_amount)?;
+
+        let surplus = available - token_amount;
+        if surplus > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        from: ctx.accounts.curve_token_account.to_account_info(),
+                        authority: ctx.accounts.curve_vault.to_account_info(),
+                    },
+                    seeds_slice,
+                ),
+                surplus,
+            )?;
+        }
+
+        // Update state AFTER all CPIs
+        let launch_data = &mut ctx.accounts.launch_data;
+        launch_data.lp_sol_amount = sol_amount;
+        launch_data.lp_token_amount = token_amount;
+
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.graduated = true;
+
+        msg!("Bonding curve graduated for {}", token_mint);
+        msg!("LP created: {} tokens + {} SOL, {} surplus tokens burned", token_amount, sol_amount, surplus);
+        msg!("LP tokens locked in custody contract");
+
+        Ok(())
+    }
+}
+
+// Helper function to read the TokenMetadata of a launch mint (None for SPL Token mints)
+fn mint_metadata(mint: &AccountInfo) -> Result<Option<TokenMetadata>> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(Some(state.get_variable_len_extension::<TokenMetadata>()?))
+}
+
+// Helper function to check launch arguments against the metadata written by create_launch_mint
+fn check_mint_metadata(mint: &AccountInfo, name: &str, symbol: &str, uri: &str) -> Result<()> {
+    if let Some(metadata) = mint_metadata(mint)? {
+        require!(
+            metadata.name == name && metadata.symbol == symbol && metadata.uri == uri,
+            LaunchError::MetadataMismatch
+        );
+    }
+    Ok(())
+}
+
+// Helper function to check curve parameters at launch
+fn validate_curve(curve: &CurveType, start_price: u64, curve_supply: u64) -> Result<()> {
+    require!(start_price > 0, LaunchError::InvalidCurveConfig);
+
+    match curve {
+        CurveType::Linear { end_price } => {
+            require!(*end_price >= start_price, LaunchError::InvalidCurveConfig);
+        }
+        CurveType::Exponential { growth_bps, step_size } => {
+            require!(*growth_bps > 0 && *step_size > 0, LaunchError::InvalidCurveConfig);
+            require!(
+                curve_supply.div_ceil(*step_size) <= MAX_CURVE_STEPS,
+                LaunchError::InvalidCurveConfig
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// Helper function to divide with the requested rounding direction
+fn div_round(numerator: u128, denominator: u128, round_up: bool) -> u128 {
+    if round_up {
+        numerator.div_ceil(denominator)
+    } else {
+        numerator / denominator
+    }
+}
+
+impl BondingCurve {
+    /// Spot price once `supply` tokens are sold (lamports per base unit, scaled by 1e9)
+    pub fn price_at(&self, supply: u64) -> Result<u128> {
+        match self.curve {
+            CurveType::Linear { end_price } => {
+                // p(s) = p0 + (p1 - p0) * s / S
+                let slope = (end_price - self.start_price) as u128;
+                let increase = slope
+                    .checked_mul(supply as u128)
+                    .ok_or(LaunchError::MathOverflow)?
+                    / self.curve_supply as u128;
+                Ok(self.start_price as u128 + increase)
+            }
+            CurveType::Exponential { growth_bps, step_size } => {
+                // Price grows by growth_bps for every full step of tokens sold
+                let mut price = self.start_price as u128;
+                for _ in 0..(supply / step_size) {
+                    price = step_price(price, growth_bps)?;
+                }
+                Ok(price)
+            }
+        }
+    }
+
+    /// SOL needed to move the curve from `from` to `to` tokens sold. Buys round up
+    /// and sells round down so the vault always covers outstanding tokens.
+    pub fn cost_between(&self, from: u64, to: u64, round_up: bool) -> Result<u64> {
+        let amount = (to - from) as u128;
+
+        let scaled_cost = match self.curve {
+            CurveType::Linear { end_price } => {
+                // Integral of p(s): p0 * (b - a) + (p1 - p0) * (b^2 - a^2) / (2S)
+                let base = (self.start_price as u128)
+                    .checked_mul(amount)
+                    .ok_or(LaunchError::MathOverflow)?;
+                let slope = (end_price - self.start_price) as u128;
+                let area = div_round(
+                    amount * (to as u128 + from as u128),
+                    2 * self.curve_supply as u128,
+                    round_up,
+                );
+                let increase = slope.checked_mul(area).ok_or(LaunchError::MathOverflow)?;
+                base.checked_add(increase).ok_or(LaunchError::MathOverflow)?
+            }
+            CurveType::Exponential { growth_bps, step_size } => {
+                // Sum price * tokens over every step touched by [from, to)
+                let mut price = self.price_at(from)?;
+                let mut position = from;
+                let mut total: u128 = 0;
+                while position < to {
+                    let step_end = (position / step_size + 1) * step_size;
+                    let tokens = step_end.min(to) - position;
+                    total = price
+                        .checked_mul(tokens as u128)
+                        .and_then(|cost| total.checked_add(cost))
+                        .ok_or(LaunchError::MathOverflow)?;
+                    position += tokens;
+                    price = step_price(price, growth_bps)?;
+                }
+                total
+            }
+        };
+
+        let cost = div_round(scaled_cost, PRICE_SCALE, round_up);
+        require!(cost <= u64::MAX as u128, LaunchError::MathOverflow);
+        Ok(cost as u64)
+    }
+
+    /// Market cap in lamports at the current curve price
+    pub fn market_cap(&self, total_supply: u64) -> Result<u64> {
+        let market_cap = self
+            .price_at(self.tokens_sold)?
+            .checked_mul(total_supply as u128)
+            .ok_or(LaunchError::MathOverflow)?
+            / PRICE_SCALE;
+        Ok(market_cap.min(u64::MAX as u128) as u64)
+    }
+}
+
+// Helper function to advance an exponential curve by one step
+fn step_price(price: u128, growth_bps: u16) -> Result<u128> {
+    Ok(price
+        .checked_mul(10000 + growth_bps as u128)
+        .ok_or(LaunchError::MathOverflow)?
+        / 10000)
 }
 
 // ===== ACCOUNT CONTEXTS =====
 
+#[derive(Accounts)]
+pub struct CreateLaunchMint<'info> {
+    /// New mint keypair, created and initialized in the instruction
+    #[account(mut)]
+    pub token_mint: Signer<'info>,
+
+    /// CHECK: Future launch_data PDA, set as mint authority and metadata update authority
+    #[account(seeds = [b"launch_data", token_mint.key().as_ref()], bump)]
+    pub launch_data: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct LaunchToken<'info> {
     #[account(
@@ -188,17 +842,18 @@ pub struct LaunchToken<'info> {
         payer = creator,
         seeds = [b"launch_data", token_mint.key().as_ref()],
         bump,
-        space = 8 + 32 + 32 + 32 + 10 + 200 + 8 + 8 + 8 + 8 + 1
+        space = 8 + 32 + 32 + (4 + 32) + (4 + 10) + (4 + 200) + 8 + 8 + 8 + 8 + 1
     )]
     pub launch_data: Account<'info, LaunchData>,
 
+    /// Fresh mint from create_launch_mint
     #[account(
-        init,
-        payer = creator,
+        mut,
         mint::decimals = 6,
         mint::authority = launch_data,
+        constraint = token_mint.supply == 0 && token_mint.freeze_authority.is_none() @ LaunchError::MintAlreadyUsed
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
@@ -206,7 +861,7 @@ pub struct LaunchToken<'info> {
         token::mint = token_mint,
         token::authority = creator,
     )]
-    pub creator_token_account: Account<'info, TokenAccount>,
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
 
     // Temporary account to hold tokens for LP creation
     #[account(
@@ -217,7 +872,7 @@ pub struct LaunchToken<'info> {
         seeds = [b"temp_tokens", token_mint.key().as_ref()],
         bump
     )]
-    pub launch_token_temp_account: Account<'info, TokenAccount>,
+    pub launch_token_temp_account: InterfaceAccount<'info, TokenAccount>,
 
     // Cross-program accounts
     /// CHECK: Project tracker account (created by CPI)
@@ -255,12 +910,15 @@ pub struct LaunchToken<'info> {
     /// CHECK: LP custody program
     pub lp_custody_program: AccountInfo<'info>,
 
+    /// CHECK: Protocol config (launch protection settings), validated by apeout_swap
+    pub protocol_config: AccountInfo<'info>,
+
     /// CHECK: ApeOut swap program
     pub apeout_swap_program: AccountInfo<'info>,
 
     #[account(mut)]
     pub creator: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -278,7 +936,259 @@ pub struct UpdateMetadata<'info> {
         bump = launch_data.bump
     )]
     pub launch_data: Account<'info, LaunchData>,
+    /// CHECK: The launch's mint; Token-2022 mints carry their metadata on it
+    #[account(mut, address = launch_data.token_mint)]
+    pub token_mint: AccountInfo<'info>,
+    #[account(mut)]
     pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateMetadata<'info> {
+    // Rewrite the on-mint fields that differ from launch_data, signed by launch_data
+    // as update authority. Token-2022 resizes the mint but doesn't fund it, so the
+    // creator tops up its rent first.
+    fn sync_mint_metadata(&self) -> Result<()> {
+        let mint = self.token_mint.to_account_info();
+        let mut metadata = match mint_metadata(&mint)? {
+            Some(metadata) => metadata,
+            None => return Ok(()),
+        };
+
+        let launch_data = &self.launch_data;
+        let updates: Vec<(Field, String)> = [
+            (Field::Name, &metadata.name, &launch_data.name),
+            (Field::Symbol, &metadata.symbol, &launch_data.symbol),
+            (Field::Uri, &metadata.uri, &launch_data.uri),
+        ]
+        .into_iter()
+        .filter(|(_, current, wanted)| current != wanted)
+        .map(|(field, _, wanted)| (field, wanted.clone()))
+        .collect();
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let old_size = metadata.tlv_size_of()?;
+        for (field, value) in &updates {
+            metadata.update(field.clone(), value.clone());
+        }
+        let new_len = mint.data_len() + metadata.tlv_size_of()? - old_size;
+        let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(mint.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.creator.to_account_info(),
+                        to: mint.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+
+        let seeds = &[
+            b"launch_data",
+            launch_data.token_mint.as_ref(),
+            &[launch_data.bump],
+        ];
+        for (field, value) in updates {
+            let ix = spl_token_metadata_interface::instruction::update_field(
+                &self.token_program.key(),
+                &mint.key(),
+                &launch_data.key(),
+                field,
+                value,
+            );
+            invoke_signed(
+                &ix,
+                &[mint.clone(), launch_data.to_account_info()],
+                &[seeds],
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct LaunchTokenWithCurve<'info> {
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"launch_data", token_mint.key().as_ref()],
+        bump,
+        space = 8 + 32 + 32 + (4 + 32) + (4 + 10) + (4 + 200) + 8 + 8 + 8 + 8 + 1
+    )]
+    pub launch_data: Account<'info, LaunchData>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"bonding_curve", token_mint.key().as_ref()],
+        bump,
+        space = 8 + 32 + (1 + 8 + 2) + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 1
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    /// Fresh mint from create_launch_mint
+    #[account(
+        mut,
+        mint::decimals = 6,
+        mint::authority = launch_data,
+        constraint = token_mint.supply == 0 && token_mint.freeze_authority.is_none() @ LaunchError::MintAlreadyUsed
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        token::mint = token_mint,
+        token::authority = creator,
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: System-owned PDA holding curve SOL and owning the curve reserve
+    #[account(
+        mut,
+        seeds = [b"curve_vault", token_mint.key().as_ref()],
+        bump
+    )]
+    pub curve_vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        token::mint = token_mint,
+        token::authority = curve_vault,
+        seeds = [b"curve_tokens", token_mint.key().as_ref()],
+        bump
+    )]
+    pub curve_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TradeOnCurve<'info> {
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(address = bonding_curve.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Curve SOL vault (PDA)
+    #[account(
+        mut,
+        seeds = [b"curve_vault", bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.vault_bump
+    )]
+    pub curve_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"curve_tokens", bonding_curve.token_mint.as_ref()],
+        bump
+    )]
+    pub curve_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = token_mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GraduateCurve<'info> {
+    #[account(
+        mut,
+        seeds = [b"launch_data", bonding_curve.token_mint.as_ref()],
+        bump = launch_data.bump
+    )]
+    pub launch_data: Account<'info, LaunchData>,
+
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(mut, address = bonding_curve.token_mint)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Curve SOL vault (PDA), becomes the pool creator
+    #[account(
+        mut,
+        seeds = [b"curve_vault", bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.vault_bump
+    )]
+    pub curve_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"curve_tokens", bonding_curve.token_mint.as_ref()],
+        bump
+    )]
+    pub curve_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Cross-program accounts
+    /// CHECK: Project tracker account (created by CPI)
+    #[account(mut)]
+    pub project_tracker: AccountInfo<'info>,
+
+    /// CHECK: LP vault account (created by CPI)
+    #[account(mut)]
+    pub lp_vault: AccountInfo<'info>,
+
+    /// CHECK: Swap pool account (created by CPI)
+    #[account(mut)]
+    pub swap_pool: AccountInfo<'info>,
+
+    /// CHECK: LP mint account (created by CPI)
+    #[account(mut)]
+    pub lp_mint: AccountInfo<'info>,
+
+    /// CHECK: Pool token account (created by CPI)
+    #[account(mut)]
+    pub pool_token_account: AccountInfo<'info>,
+
+    /// CHECK: Pool SOL account (created by CPI)
+    #[account(mut)]
+    pub pool_sol_account: AccountInfo<'info>,
+
+    /// CHECK: Custody LP account (created by CPI)
+    #[account(mut)]
+    pub custody_lp_account: AccountInfo<'info>,
+
+    // Programs
+    /// CHECK: Project status tracker program
+    pub project_status_tracker_program: AccountInfo<'info>,
+
+    /// CHECK: LP custody program
+    pub lp_custody_program: AccountInfo<'info>,
+
+    /// CHECK: Protocol config (launch protection settings), validated by apeout_swap
+    pub protocol_config: AccountInfo<'info>,
+
+    pub apeout_swap_program: Program<'info, apeout_swap::program::ApeoutSwap>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 // ===== DATA STRUCTURES =====
@@ -297,6 +1207,39 @@ pub struct LaunchData {
     pub bump: u8,                  // PDA bump
 }
 
+#[account]
+pub struct BondingCurve {
+    pub token_mint: Pubkey,          // Token sold on the curve
+    pub curve: CurveType,            // Price curve shape
+    pub start_price: u64,            // Price at zero sold (lamports per base unit, 1e9 scaled)
+    pub curve_supply: u64,           // Tokens for sale on the curve
+    pub tokens_sold: u64,            // Tokens currently sold
+    pub sol_raised: u64,             // SOL held for sold tokens
+    pub graduation_sol: u64,         // Graduate once this much SOL is raised (0 = off)
+    pub graduation_market_cap: u64,  // Graduate once market cap reaches this (0 = off)
+    pub is_complete: bool,           // Trading on the curve has ended
+    pub graduated: bool,             // Liquidity migrated to apeout_swap
+    pub bump: u8,                    // PDA bump
+    pub vault_bump: u8,              // Curve vault PDA bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CurveType {
+    /// Price rises linearly from start_price to end_price over the curve supply
+    Linear { end_price: u64 },
+    /// Price rises by growth_bps for every step_size tokens sold
+    Exponential { growth_bps: u16, step_size: u64 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CurveParams {
+    pub curve: CurveType,
+    pub start_price: u64,
+    pub curve_supply: u64,
+    pub graduation_sol: u64,
+    pub graduation_market_cap: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct LaunchInfo {
     pub creator: Pubkey,
@@ -310,6 +1253,21 @@ pub struct LaunchInfo {
     pub launch_time: i64,
 }
 
+// ===== EVENTS =====
+
+#[event]
+pub struct TokenLaunched {
+    pub token_mint: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub initial_supply: u64,
+    pub lp_token_amount: u64,      // Tokens seeded into the AMM pool (0 until a curve graduates)
+    pub lp_sol_amount: u64,        // SOL seeded into the AMM pool (0 until a curve graduates)
+    pub on_curve: bool,            // Launched on a bonding curve rather than straight into the AMM
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum LaunchError {
     #[msg("Token name is too long (max 32 characters).")]
@@ -326,4 +1284,22 @@ pub enum LaunchError {
     InvalidLPTokenAmount,
     #[msg("Only the token creator can update metadata.")]
     UnauthorizedUpdate,
+    #[msg("Invalid bonding curve configuration.")]
+    InvalidCurveConfig,
+    #[msg("Invalid amount provided.")]
+    InvalidAmount,
+    #[msg("Slippage tolerance exceeded.")]
+    SlippageExceeded,
+    #[msg("Bonding curve is complete, trading has moved to the pool.")]
+    CurveComplete,
+    #[msg("Bonding curve has not reached its graduation threshold.")]
+    CurveNotComplete,
+    #[msg("Bonding curve has already graduated.")]
+    AlreadyGraduated,
+    #[msg("Mathematical overflow occurred.")]
+    MathOverflow,
+    #[msg("Launch mint must be fresh: no supply and no freeze authority.")]
+    MintAlreadyUsed,
+    #[msg("Launch metadata does not match the mint's metadata.")]
+    MetadataMismatch,
 }
\ No newline at end of file